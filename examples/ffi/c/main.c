@@ -30,6 +30,6 @@ int main(int argc, char *argv[]) {
     char *private_key = filecoin_signer_extended_key_private_key(extended_key, error);
     manage_error(extended_key, error);
     assert(strcmp(private_key, "f15716d3b003b304b8055d9cc62e6b9c869d56cc930c3858d4d7c31f5f53f14a") == 0);
-    filecoin_signer_string_free(private_key);
+    filecoin_signer_secret_string_free(private_key);
     free_resources(extended_key, error);
 }
\ No newline at end of file
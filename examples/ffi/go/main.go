@@ -25,7 +25,7 @@ func main() {
 		if C.GoString(private_key) != "f15716d3b003b304b8055d9cc62e6b9c869d56cc930c3858d4d7c31f5f53f14a" {
 			panic("Bad key");
 		}
-		C.filecoin_signer_string_free(private_key);
+		C.filecoin_signer_secret_string_free(private_key);
 	}
 
 	C.filecoin_signer_extended_key_free(extended_key);
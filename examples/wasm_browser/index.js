@@ -20,11 +20,11 @@ let key = wasm.keyDerive(mnemonic, 'm/44\'/461\'/0/0/0', '')
 log('<h2>[wasm.key_derive]</h2>')
 log(`<b>address      </b> ${key.address}`)
 log(`<b>public  key  </b> ${key.public_hexstring}`)
-log(`<b>private key  </b> ${key.private_hexstring}`)
+log(`<b>private key  </b> ${key.exportPrivateHexstring()}`)
 log(`<b>public array </b> ${key.public_raw}`)
-log(`<b>private array</b> ${key.private_raw}`)
+log(`<b>private array</b> ${key.exportPrivateRaw()}`)
 log(`<b>public base64</b> ${key.public_base64}`)
-log(`<b>private base64</b> ${key.private_base64}`)
+log(`<b>private base64</b> ${key.exportPrivateBase64()}`)
 
 /////////////////////////////////
 // Recover key
@@ -34,9 +34,9 @@ let recovered_key = wasm.keyRecover(Buffer.from('6a1a68774457742a8bc69db5491df5a
 log('<h2>[wasm.key_recover]</h2>')
 log(`<b>address      </b> ${recovered_key.address}`)
 log(`<b>public  key  </b> ${recovered_key.public_hexstring}`)
-log(`<b>private key  </b> ${recovered_key.private_hexstring}`)
+log(`<b>private key  </b> ${recovered_key.exportPrivateHexstring()}`)
 log(`<b>public array </b> ${recovered_key.public_raw}`)
-log(`<b>private array</b> ${recovered_key.private_raw}`)
+log(`<b>private array</b> ${recovered_key.exportPrivateRaw()}`)
 
 /////////////////////////////////
 // Sign transaction
@@ -57,12 +57,12 @@ const unsigned_tx = {
 }
 
 console.log('About to call wasm.transactionSignLotus():')
-wasm.transactionSignLotus(unsigned_tx, key.private_raw)
+wasm.transactionSignLotus(unsigned_tx, key.exportPrivateRaw())
 console.log('Done calling wasm.transactionSignLotus()')
 
 log(`unsigned_tx = ${JSON.stringify(unsigned_tx, 0, 4)}`)
 
-let signed_tx = wasm.transactionSign(unsigned_tx, key.private_raw)
+let signed_tx = wasm.transactionSign(unsigned_tx, key.exportPrivateRaw())
 
 log('\n...sign...\n')
 log(`signed_tx = ${JSON.stringify(signed_tx, 0, 4)}`)
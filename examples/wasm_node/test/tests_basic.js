@@ -43,11 +43,11 @@ describeCall('keyDerive', function() {
 
     console.log('Public Key Raw         :', keypair.public_raw)
     console.log('Public Key             :', keypair.public_hexstring)
-    console.log('Private                :', keypair.private_hexstring)
+    console.log('Private                :', keypair.exportPrivateHexstring())
     console.log('Address                :', keypair.address)
 
     const expected_keys = MASTER_NODE.derivePath(child.path)
-    assert.strictEqual(keypair.private_hexstring, expected_keys.privateKey.toString('hex'))
+    assert.strictEqual(keypair.exportPrivateHexstring(), expected_keys.privateKey.toString('hex'))
     assert.strictEqual(keypair.address, child.address)
   })
 
@@ -60,11 +60,11 @@ describeCall('keyDerive', function() {
 
     console.log('Public Key Raw         :', keypair.public_raw)
     console.log('Public Key             :', keypair.public_hexstring)
-    console.log('Private                :', keypair.private_hexstring)
+    console.log('Private                :', keypair.exportPrivateHexstring())
     console.log('Address                :', keypair.address)
 
     const expected_keys = MASTER_NODE.derivePath(child.path)
-    assert.strictEqual(keypair.private_hexstring, expected_keys.privateKey.toString('hex'))
+    assert.strictEqual(keypair.exportPrivateHexstring(), expected_keys.privateKey.toString('hex'))
     assert(keypair.address.startsWith('t'))
   })
 
@@ -89,14 +89,14 @@ describeCall('keyDerive', function() {
 
     console.log('Public Key Raw         :', keypair.public_raw)
     console.log('Public Key             :', keypair.public_hexstring)
-    console.log('Private                :', keypair.private_hexstring)
+    console.log('Private                :', keypair.exportPrivateHexstring())
     console.log('Address                :', keypair.address)
 
     const seed = bip39.mnemonicToSeedSync(dataWallet.mnemonic, password)
     const node = bip32.fromSeed(seed)
 
     const expected_keys = node.derivePath('m/44\'/461\'/0/0/1')
-    assert.strictEqual(keypair.private_hexstring, expected_keys.privateKey.toString('hex'))
+    assert.strictEqual(keypair.exportPrivateHexstring(), expected_keys.privateKey.toString('hex'))
   })
 
   it('should not match the key with the different password', function() {
@@ -104,14 +104,14 @@ describeCall('keyDerive', function() {
 
     console.log('Public Key Raw         :', keypair.public_raw)
     console.log('Public Key             :', keypair.public_hexstring)
-    console.log('Private                :', keypair.private_hexstring)
+    console.log('Private                :', keypair.exportPrivateHexstring())
     console.log('Address                :', keypair.address)
 
     const seed = bip39.mnemonicToSeedSync(dataWallet.mnemonic, 'lol')
     const node = bip32.fromSeed(seed)
 
     const expected_keys = node.derivePath('m/44\'/461\'/0/0/1')
-    assert.notEqual(keypair.private_hexstring, expected_keys.privateKey.toString('hex'))
+    assert.notEqual(keypair.exportPrivateHexstring(), expected_keys.privateKey.toString('hex'))
   })
 
 
@@ -153,11 +153,11 @@ describe('keyDeriveFromSeed', function() {
 
     console.log('Public Key Raw         :', keypair.public_raw)
     console.log('Public Key             :', keypair.public_hexstring)
-    console.log('Private                :', keypair.private_hexstring)
+    console.log('Private                :', keypair.exportPrivateHexstring())
     console.log('Address                :', keypair.address)
 
     const expected_keys = MASTER_NODE.derivePath(child.path)
-    assert.strictEqual(keypair.private_hexstring, expected_keys.privateKey.toString('hex'))
+    assert.strictEqual(keypair.exportPrivateHexstring(), expected_keys.privateKey.toString('hex'))
     assert.strictEqual(keypair.address, child.address)
   })
 
@@ -169,11 +169,11 @@ describe('keyDeriveFromSeed', function() {
 
     console.log('Public Key Raw         :', keypair.public_raw)
     console.log('Public Key             :', keypair.public_hexstring)
-    console.log('Private                :', keypair.private_hexstring)
+    console.log('Private                :', keypair.exportPrivateHexstring())
     console.log('Address                :', keypair.address)
 
     const expected_keys = MASTER_NODE.derivePath(child.path)
-    assert.strictEqual(keypair.private_hexstring, expected_keys.privateKey.toString('hex'))
+    assert.strictEqual(keypair.exportPrivateHexstring(), expected_keys.privateKey.toString('hex'))
     assert.strictEqual(keypair.address, child.address)
   })
 })
@@ -189,11 +189,11 @@ describe('keyRecover', function() {
 
     console.log('Public Key Raw         :', recoveredKey.public_raw)
     console.log('Public Key             :', recoveredKey.public_hexstring)
-    console.log('Private                :', recoveredKey.private_hexstring)
-    console.log('Private Key (base64)   :', recoveredKey.private_base64)
+    console.log('Private                :', recoveredKey.exportPrivateHexstring())
+    console.log('Private Key (base64)   :', recoveredKey.exportPrivateBase64())
     console.log('Address                :', recoveredKey.address)
 
-    assert.strictEqual(recoveredKey.private_hexstring, expected_keys.privateKey.toString('hex'))
+    assert.strictEqual(recoveredKey.exportPrivateHexstring(), expected_keys.privateKey.toString('hex'))
     assert.strictEqual(recoveredKey.address, child.address)
   })
 
@@ -205,12 +205,12 @@ describe('keyRecover', function() {
 
     console.log('Public Key Raw         :', recoveredKey.public_raw)
     console.log('Public Key (hex)       :', recoveredKey.public_hexstring)
-    console.log('Private Key (hex)      :', recoveredKey.private_hexstring)
+    console.log('Private Key (hex)      :', recoveredKey.exportPrivateHexstring())
     console.log('Public Key (base64)    :', recoveredKey.public_base64)
-    console.log('Private Key (base64)   :', recoveredKey.private_base64)
+    console.log('Private Key (base64)   :', recoveredKey.exportPrivateBase64())
     console.log('Address                :', recoveredKey.address)
 
-    assert.strictEqual(recoveredKey.private_hexstring, expected_keys.privateKey.toString('hex'))
+    assert.strictEqual(recoveredKey.exportPrivateHexstring(), expected_keys.privateKey.toString('hex'))
     assert.strictEqual(recoveredKey.address, child.address)
   })
 })
@@ -221,7 +221,7 @@ describeCall('keyRecoverBLS', function() {
 
     console.log('Public Key Raw         :', recoveredKey.public_raw)
     console.log('Public Key             :', recoveredKey.public_hexstring)
-    console.log('Private                :', recoveredKey.private_hexstring)
+    console.log('Private                :', recoveredKey.exportPrivateHexstring())
     console.log('Address                :', recoveredKey.address)
 
     assert.strictEqual(recoveredKey.address, dataWallet.bls_address)
@@ -0,0 +1,344 @@
+use std::fmt;
+use std::str::FromStr;
+
+use blake2b_simd::Params;
+use data_encoding::BASE32_NOPAD;
+use forest_address::{Address, Error, Network};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Delegated address protocol (`f4`), not supported by `forest_address`
+pub const DELEGATED_PROTOCOL: u8 = 4;
+
+/// Maximum length of a delegated sub-address
+pub const MAX_SUBADDRESS_LEN: usize = 54;
+
+const CHECKSUM_LEN: usize = 4;
+
+/// Delegated (`f4`) address: an address managed by the actor whose ID is `namespace`
+/// (e.g `f410...` addresses are managed by the Ethereum Address Manager)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DelegatedAddress {
+    namespace: u64,
+    subaddress: Vec<u8>,
+    network: Network,
+}
+
+impl DelegatedAddress {
+    /// Create a mainnet delegated address
+    pub fn new(namespace: u64, subaddress: &[u8]) -> Result<Self, Error> {
+        if subaddress.len() > MAX_SUBADDRESS_LEN {
+            return Err(Error::InvalidPayload);
+        }
+
+        Ok(DelegatedAddress {
+            namespace,
+            subaddress: subaddress.to_vec(),
+            network: Network::Mainnet,
+        })
+    }
+
+    /// Decode an address from its bytes (`protocol || leb128(namespace) || subaddress`), on the
+    /// same default network as the addresses decoded by `forest_address`
+    pub fn from_bytes(bz: &[u8]) -> Result<Self, Error> {
+        match bz.split_first() {
+            Some((&DELEGATED_PROTOCOL, payload)) => {
+                let (namespace, read) = read_uvarint(payload).ok_or(Error::InvalidPayload)?;
+                let mut address = DelegatedAddress::new(namespace, &payload[read..])?;
+                address.set_network(Address::new_id(0).network());
+                Ok(address)
+            }
+            Some(_) => Err(Error::UnknownProtocol),
+            None => Err(Error::InvalidLength),
+        }
+    }
+
+    pub fn namespace(&self) -> u64 {
+        self.namespace
+    }
+
+    pub fn subaddress(&self) -> &[u8] {
+        &self.subaddress
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn set_network(&mut self, network: Network) -> &mut Self {
+        self.network = network;
+        self
+    }
+
+    /// Address payload (`leb128(namespace) || subaddress`)
+    pub fn payload_bytes(&self) -> Vec<u8> {
+        let mut payload = write_uvarint(self.namespace);
+        payload.extend_from_slice(&self.subaddress);
+        payload
+    }
+
+    /// Address bytes (`protocol || payload`), as serialized on chain
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bz = vec![DELEGATED_PROTOCOL];
+        bz.extend(self.payload_bytes());
+        bz
+    }
+
+    fn checksum(&self) -> Vec<u8> {
+        Params::new()
+            .hash_length(CHECKSUM_LEN)
+            .to_state()
+            .update(&self.to_bytes())
+            .finalize()
+            .as_bytes()
+            .to_vec()
+    }
+}
+
+impl fmt::Display for DelegatedAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.network {
+            Network::Mainnet => 'f',
+            Network::Testnet => 't',
+        };
+        let mut data = self.subaddress.clone();
+        data.extend(self.checksum());
+
+        write!(
+            f,
+            "{}{}{}f{}",
+            prefix,
+            DELEGATED_PROTOCOL,
+            self.namespace,
+            BASE32_NOPAD.encode(&data).to_ascii_lowercase()
+        )
+    }
+}
+
+impl FromStr for DelegatedAddress {
+    type Err = Error;
+
+    fn from_str(addr: &str) -> Result<Self, Error> {
+        let network = match addr.get(0..1) {
+            Some("f") => Network::Mainnet,
+            Some("t") => Network::Testnet,
+            _ => return Err(Error::UnknownNetwork),
+        };
+
+        if addr.get(1..2) != Some("4") {
+            return Err(Error::UnknownProtocol);
+        }
+
+        // `f4<namespace>f<base32(subaddress || checksum)>`
+        let rest = &addr[2..];
+        let separator = rest.find('f').ok_or(Error::InvalidPayload)?;
+        let (namespace, encoded) = (&rest[..separator], &rest[separator + 1..]);
+
+        if namespace.is_empty()
+            || (namespace.len() > 1 && namespace.starts_with('0'))
+            || !namespace.bytes().all(|c| c.is_ascii_digit())
+        {
+            return Err(Error::InvalidPayload);
+        }
+        let namespace = u64::from_str(namespace).map_err(|_| Error::InvalidPayload)?;
+
+        // Only lowercase base32 is a valid address encoding
+        if encoded.bytes().any(|c| c.is_ascii_uppercase()) {
+            return Err(Error::InvalidPayload);
+        }
+        let data = BASE32_NOPAD
+            .decode(encoded.to_ascii_uppercase().as_bytes())
+            .map_err(|_| Error::InvalidPayload)?;
+
+        if data.len() < CHECKSUM_LEN {
+            return Err(Error::InvalidLength);
+        }
+        let (subaddress, checksum) = data.split_at(data.len() - CHECKSUM_LEN);
+
+        let mut address = DelegatedAddress::new(namespace, subaddress)?;
+        if address.checksum() != checksum {
+            return Err(Error::InvalidChecksum);
+        }
+        address.set_network(network);
+
+        Ok(address)
+    }
+}
+
+impl Serialize for DelegatedAddress {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serde_bytes::Serialize::serialize(&self.to_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DelegatedAddress {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bz: Vec<u8> = serde_bytes::Deserialize::deserialize(deserializer)?;
+        DelegatedAddress::from_bytes(&bz).map_err(de::Error::custom)
+    }
+}
+
+/// Any Filecoin address: the protocols supported by `forest_address` (ID, secp256k1, actor and
+/// BLS) or a delegated (`f4`) address
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnyAddress {
+    Address(Address),
+    Delegated(DelegatedAddress),
+}
+
+impl AnyAddress {
+    /// Decode an address from its bytes (`protocol || payload`)
+    pub fn from_bytes(bz: &[u8]) -> Result<Self, Error> {
+        match bz.first() {
+            Some(&DELEGATED_PROTOCOL) => {
+                Ok(AnyAddress::Delegated(DelegatedAddress::from_bytes(bz)?))
+            }
+            _ => Ok(AnyAddress::Address(Address::from_bytes(bz)?)),
+        }
+    }
+
+    /// Address protocol byte (0: ID, 1: secp256k1, 2: actor, 3: BLS, 4: delegated)
+    pub fn protocol(&self) -> u8 {
+        match self {
+            AnyAddress::Address(address) => address.protocol() as u8,
+            AnyAddress::Delegated(_) => DELEGATED_PROTOCOL,
+        }
+    }
+
+    pub fn payload_bytes(&self) -> Vec<u8> {
+        match self {
+            AnyAddress::Address(address) => address.payload_bytes(),
+            AnyAddress::Delegated(address) => address.payload_bytes(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            AnyAddress::Address(address) => address.to_bytes(),
+            AnyAddress::Delegated(address) => address.to_bytes(),
+        }
+    }
+
+    pub fn network(&self) -> Network {
+        match self {
+            AnyAddress::Address(address) => address.network(),
+            AnyAddress::Delegated(address) => address.network(),
+        }
+    }
+
+    pub fn set_network(&mut self, network: Network) -> &mut Self {
+        match self {
+            AnyAddress::Address(address) => {
+                address.set_network(network);
+            }
+            AnyAddress::Delegated(address) => {
+                address.set_network(network);
+            }
+        }
+        self
+    }
+
+    /// Return the `forest_address` address, `None` for a delegated address
+    pub fn as_address(&self) -> Option<&Address> {
+        match self {
+            AnyAddress::Address(address) => Some(address),
+            AnyAddress::Delegated(_) => None,
+        }
+    }
+
+    pub fn is_delegated(&self) -> bool {
+        matches!(self, AnyAddress::Delegated(_))
+    }
+}
+
+impl From<Address> for AnyAddress {
+    fn from(address: Address) -> Self {
+        AnyAddress::Address(address)
+    }
+}
+
+impl From<DelegatedAddress> for AnyAddress {
+    fn from(address: DelegatedAddress) -> Self {
+        AnyAddress::Delegated(address)
+    }
+}
+
+impl fmt::Display for AnyAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnyAddress::Address(address) => write!(f, "{}", address),
+            AnyAddress::Delegated(address) => write!(f, "{}", address),
+        }
+    }
+}
+
+impl FromStr for AnyAddress {
+    type Err = Error;
+
+    fn from_str(addr: &str) -> Result<Self, Error> {
+        if addr.get(1..2) == Some("4") {
+            Ok(AnyAddress::Delegated(DelegatedAddress::from_str(addr)?))
+        } else {
+            Ok(AnyAddress::Address(Address::from_str(addr)?))
+        }
+    }
+}
+
+impl Serialize for AnyAddress {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serde_bytes::Serialize::serialize(&self.to_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyAddress {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bz: Vec<u8> = serde_bytes::Deserialize::deserialize(deserializer)?;
+        AnyAddress::from_bytes(&bz).map_err(de::Error::custom)
+    }
+}
+
+fn write_uvarint(mut value: u64) -> Vec<u8> {
+    let mut bz = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bz.push(byte);
+            return bz;
+        }
+        bz.push(byte | 0x80);
+    }
+}
+
+/// Read an unsigned LEB128 value, return the value and the number of bytes read
+fn read_uvarint(bz: &[u8]) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+
+    for (i, byte) in bz.iter().enumerate().take(10) {
+        // The 10th byte can only hold the most significant bit
+        if i == 9 && *byte > 1 {
+            return None;
+        }
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            // Reject non minimal encodings (trailing zero bytes)
+            if i > 0 && *byte == 0 {
+                return None;
+            }
+            return Some((value, i + 1));
+        }
+    }
+
+    None
+}
@@ -1,3 +1,4 @@
+pub mod address;
 pub mod multisig;
 pub mod paych;
 
@@ -25,6 +26,15 @@ pub struct ExecParams {
     pub constructor_params: Serialized,
 }
 
+/// Exec Return
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct ExecReturn {
+    /// ID based address of the created actor
+    pub id_address: Address,
+    /// Reorg safe address of the created actor
+    pub robust_address: Address,
+}
+
 lazy_static! {
     pub static ref SYSTEM_ACTOR_ADDR: Address         = Address::new_id(0);
     pub static ref INIT_ACTOR_ADDR: Address           = Address::new_id(1);
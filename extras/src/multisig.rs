@@ -6,6 +6,8 @@ use num_bigint::bigint_ser;
 use num_derive::FromPrimitive;
 use serde::{Deserialize, Serialize};
 
+use crate::address::AnyAddress;
+
 /// Transaction ID type
 #[derive(Clone, Copy, Default, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -43,18 +45,31 @@ pub struct ConstructorParamsV1 {
 /// Propose method call parameters
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ProposeParams {
-    pub to: Address,
+    pub to: AnyAddress,
     #[serde(with = "bigint_ser")]
     pub value: TokenAmount,
     pub method: MethodNum,
     pub params: Serialized,
 }
 
+/// Propose method call return
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct ProposeReturn {
+    /// ID of the proposed transaction
+    pub txn_id: TxnID,
+    /// `true` if the transaction was applied (enough approvals), `false` if only proposed
+    pub applied: bool,
+    /// Exit code of the transaction, ignored if not applied
+    pub code: i64,
+    /// Return value of the transaction, ignored if not applied
+    pub ret: Serialized,
+}
+
 /// Proposal hash data
 #[derive(Clone, PartialEq, Debug, Serialize_tuple, Deserialize_tuple)]
 pub struct ProposalHashData {
     pub requester: Address,
-    pub to: Address,
+    pub to: AnyAddress,
     #[serde(with = "bigint_ser")]
     pub value: TokenAmount,
     pub method: u64,
@@ -6,6 +6,8 @@ use forest_vm::{MethodNum, Serialized, TokenAmount, METHOD_CONSTRUCTOR};
 use num_bigint::{bigint_ser, BigInt};
 use num_derive::FromPrimitive;
 
+use crate::address::AnyAddress;
+
 /// Maximum number of lanes in a channel
 pub const LANE_LIMIT: usize = 256;
 
@@ -16,7 +18,7 @@ pub const SETTLE_DELAY: ChainEpoch = 1;
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct ConstructorParams {
     pub from: Address,
-    pub to: Address,
+    pub to: AnyAddress,
 }
 
 /// A given payment channel actor is established by `from`
@@ -16,7 +16,7 @@ fn main() {
                         assert_eq!(&s.0[..], &bls.0[..]);
                     }
                 }
-                Signature::SignatureSECP256K1(s) => {
+                Signature::SignatureSECP256K1(s) | Signature::SignatureDelegated(s) => {
                     if let Ok(secp256k1) = SignatureSECP256K1::try_from(s.to_string()) {
                         assert_eq!(&s.0[..], &secp256k1.0[..]);
                     }
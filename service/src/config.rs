@@ -54,12 +54,24 @@ impl Default for RemoteNodeSection {
 pub struct ServiceSection {
     /// Service HTTP address host:port
     pub address: String,
+    /// Directory where the encrypted keystore entries are stored
+    #[serde(default = "default_keystore_path")]
+    pub keystore_path: String,
+    /// Return the private key from `keystore_unlock` (disabled by default)
+    #[serde(default)]
+    pub keystore_export_private_keys: bool,
+}
+
+fn default_keystore_path() -> String {
+    "keystore".to_owned()
 }
 
 impl Default for ServiceSection {
     fn default() -> Self {
         Self {
             address: "127.0.0.1:3030".to_owned(),
+            keystore_path: default_keystore_path(),
+            keystore_export_private_keys: false,
         }
     }
 }
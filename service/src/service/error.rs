@@ -46,6 +46,9 @@ pub enum ServiceError {
     /// Serde Json Error
     #[error("Serde JSON | {0}")]
     SerdeError(#[from] serde_json::error::Error),
+    /// Blocking task error
+    #[error("Blocking task | {0}")]
+    Blocking(#[from] tokio::task::JoinError),
     /// Generic string error
     #[cfg(feature = "cache-nonce")]
     #[error("Error | {0}")]
@@ -38,10 +38,25 @@ async fn v0_post_methods(
         "key_generate_mnemonic" => methods::key_generate_mnemonic(method_call, config).await,
         "key_derive" => methods::key_derive(method_call, config).await,
         "key_derive_from_seed" => methods::key_derive_from_seed(method_call, config).await,
+        "key_generate_bls" => methods::key_generate_bls(method_call, config).await,
+        "keystore_create" => methods::keystore_create(method_call, config).await,
+        "keystore_unlock" => methods::keystore_unlock(method_call, config).await,
+        "keystore_change_password" => methods::keystore_change_password(method_call, config).await,
+        "keystore_list" => methods::keystore_list(method_call, config).await,
         "transaction_serialize" => methods::transaction_serialize(method_call, config).await,
         "transaction_parse" => methods::transaction_parse(method_call, config).await,
+        "signed_message_serialize" => methods::signed_message_serialize(method_call, config).await,
+        "signed_message_parse" => methods::signed_message_parse(method_call, config).await,
         "sign_transaction" => methods::sign_transaction(method_call, config).await,
+        "validate_message" => methods::validate_message(method_call, config).await,
         "verify_signature" => methods::verify_signature(method_call, config).await,
+        "aggregate_signatures" => methods::aggregate_signatures(method_call, config).await,
+        "sign_message" => methods::sign_message(method_call, config).await,
+        "verify_message" => methods::verify_message(method_call, config).await,
+        "address_parse" => methods::address_parse(method_call, config).await,
+        "address_validate" => methods::address_validate(method_call, config).await,
+        "address_to_network" => methods::address_to_network(method_call, config).await,
+        "actor_address" => methods::actor_address(method_call, config).await,
         "get_status" => methods::get_status(method_call, config).await,
         "get_nonce" => methods::get_nonce(method_call, config).await,
         "send_signed_tx" => methods::send_signed_tx(method_call, config).await,
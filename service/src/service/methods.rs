@@ -1,10 +1,13 @@
 //////! Filecoin Service RPC Client
 
+use crate::application::app_config;
 use crate::config::RemoteNodeSection;
 use crate::service::client;
 use crate::service::error::ServiceError;
 use filecoin_signer::api::{SignedMessageAPI, UnsignedMessageAPI};
-use filecoin_signer::signature::Signature;
+use filecoin_signer::keystore::{DirectoryStorage, Keystore};
+use filecoin_signer::lotus::LotusSignedMessage;
+use filecoin_signer::signature::{Signature, SignatureBLS};
 use filecoin_signer::{CborBuffer, PrivateKey};
 use jsonrpc_core::{MethodCall, Success, Version};
 use serde::{Deserialize, Serialize};
@@ -21,6 +24,13 @@ pub struct SendSignedTxParamsAPI {
 pub struct SignTransactionParamsAPI {
     pub transaction: UnsignedMessageAPI,
     pub prvkey_base64: String,
+    #[serde(default)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ValidateMessageParamsAPI {
+    pub transaction: UnsignedMessageAPI,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -43,6 +53,43 @@ pub struct KeyDeriveFromSeedParamsAPI {
     pub path: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeyGenerateBLSParamsAPI {
+    pub testnet: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeystoreCreateParamsAPI {
+    pub prvkey_base64: String,
+    #[serde(default)]
+    pub bls: bool,
+    pub testnet: bool,
+    pub password: String,
+    #[serde(default)]
+    pub mnemonic: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeystoreUnlockParamsAPI {
+    pub address: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeystoreUnlockResultAPI {
+    pub address: String,
+    pub public_hexstring: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_base64: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KeystoreChangePasswordParamsAPI {
+    pub address: String,
+    pub old_password: String,
+    pub new_password: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct KeyDeriveResultAPI {
     pub private_base64: String,
@@ -62,6 +109,47 @@ pub struct VerifySignatureParamsAPI {
     pub message_hex: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AggregateSignaturesParamsAPI {
+    pub signatures_hex: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SignMessageParamsAPI {
+    pub message_hex: String,
+    pub prvkey_base64: String,
+    #[serde(default)]
+    pub bls: bool,
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VerifyMessageParamsAPI {
+    pub signature_hex: String,
+    pub message_hex: String,
+    pub address: String,
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddressParamsAPI {
+    pub address: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddressToNetworkParamsAPI {
+    pub address: String,
+    pub testnet: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ActorAddressParamsAPI {
+    pub creator: String,
+    pub nonce: u64,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GetStatusParamsAPI {
     pub cid_message: String,
@@ -75,7 +163,7 @@ pub async fn key_generate_mnemonic(
 
     let so = Success {
         jsonrpc: Some(Version::V2),
-        result: Value::from(mnemonic.0),
+        result: Value::from(mnemonic.export_phrase()),
         id: c.id,
     };
 
@@ -94,7 +182,7 @@ pub async fn key_derive(c: MethodCall, _: RemoteNodeSection) -> Result<Success,
 
     let result = KeyDeriveResultAPI {
         public_hexstring: hex::encode(&key_address.public_key.to_vec()),
-        private_base64: base64::encode(&key_address.private_key.0),
+        private_base64: key_address.private_key.export_base64(),
         address: key_address.address,
     };
 
@@ -121,7 +209,32 @@ pub async fn key_derive_from_seed(
 
     let result = KeyDeriveResultAPI {
         public_hexstring: hex::encode(&key_address.public_key.to_vec()),
-        private_base64: base64::encode(&key_address.private_key.0),
+        private_base64: key_address.private_key.export_base64(),
+        address: key_address.address,
+    };
+
+    let result_json = serde_json::to_value(&result)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: result_json,
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn key_generate_bls(
+    c: MethodCall,
+    _: RemoteNodeSection,
+) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<KeyGenerateBLSParamsAPI>()?;
+
+    let key_address = filecoin_signer::key_generate_bls(params.testnet)?;
+
+    let result = KeyDeriveResultAPI {
+        public_hexstring: hex::encode(&key_address.public_key.to_vec()),
+        private_base64: key_address.private_key.export_base64(),
         address: key_address.address,
     };
 
@@ -136,6 +249,104 @@ pub async fn key_derive_from_seed(
     Ok(so)
 }
 
+fn keystore() -> Keystore<DirectoryStorage> {
+    let keystore_path = app_config().service.keystore_path.clone();
+
+    Keystore::new(DirectoryStorage::new(keystore_path))
+}
+
+pub async fn keystore_create(c: MethodCall, _: RemoteNodeSection) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<KeystoreCreateParamsAPI>()?;
+
+    let private_key = PrivateKey::try_from(params.prvkey_base64)?;
+
+    let key_address = if params.bls {
+        filecoin_signer::key_recover_bls(&private_key, params.testnet)?
+    } else {
+        filecoin_signer::key_recover(&private_key, params.testnet)?
+    };
+
+    // Scrypt is slow on purpose, keep it off the async executor
+    let address = key_address.address.clone();
+    let (mnemonic, password) = (params.mnemonic, params.password);
+    tokio::task::spawn_blocking(move || {
+        keystore().create(&key_address, mnemonic.as_deref(), &password)
+    })
+    .await??;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(address),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn keystore_unlock(c: MethodCall, _: RemoteNodeSection) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<KeystoreUnlockParamsAPI>()?;
+
+    let unlocked_key =
+        tokio::task::spawn_blocking(move || keystore().unlock(&params.address, &params.password))
+            .await??;
+    let key_address = unlocked_key.extended_key;
+
+    // The private key only leaves the service if the configuration allows it
+    let private_base64 = if app_config().service.keystore_export_private_keys {
+        Some(key_address.private_key.export_base64())
+    } else {
+        None
+    };
+
+    let result = KeystoreUnlockResultAPI {
+        public_hexstring: hex::encode(&key_address.public_key.to_vec()),
+        private_base64,
+        address: key_address.address,
+    };
+
+    let result_json = serde_json::to_value(&result)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: result_json,
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn keystore_change_password(
+    c: MethodCall,
+    _: RemoteNodeSection,
+) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<KeystoreChangePasswordParamsAPI>()?;
+
+    tokio::task::spawn_blocking(move || {
+        keystore().change_password(&params.address, &params.old_password, &params.new_password)
+    })
+    .await??;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(true),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn keystore_list(c: MethodCall, _: RemoteNodeSection) -> Result<Success, ServiceError> {
+    let addresses = keystore().list()?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: serde_json::to_value(&addresses)?,
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
 pub async fn transaction_serialize(
     c: MethodCall,
     _: RemoteNodeSection,
@@ -172,6 +383,40 @@ pub async fn transaction_parse(
     Ok(so)
 }
 
+pub async fn signed_message_serialize(
+    c: MethodCall,
+    _: RemoteNodeSection,
+) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<SignedMessageAPI>()?;
+    let cbor_buffer = filecoin_signer::signed_message_serialize(&params)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(hex::encode(&cbor_buffer.0)),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn signed_message_parse(
+    c: MethodCall,
+    _: RemoteNodeSection,
+) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<TransctionParseParamsAPI>()?;
+    let cbor_data = CborBuffer(hex::decode(&params.cbor_hex)?);
+
+    let signed_message = filecoin_signer::signed_message_parse(&cbor_data, params.testnet)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: serde_json::to_value(&signed_message)?,
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
 pub async fn sign_transaction(
     c: MethodCall,
     _: RemoteNodeSection,
@@ -180,7 +425,11 @@ pub async fn sign_transaction(
 
     let private_key = PrivateKey::try_from(params.prvkey_base64)?;
 
-    let signed_message = filecoin_signer::transaction_sign(&params.transaction, &private_key)?;
+    let signed_message = if params.strict {
+        filecoin_signer::transaction_sign_strict(&params.transaction, &private_key)?
+    } else {
+        filecoin_signer::transaction_sign(&params.transaction, &private_key)?
+    };
 
     let so = Success {
         jsonrpc: Some(Version::V2),
@@ -191,6 +440,23 @@ pub async fn sign_transaction(
     Ok(so)
 }
 
+pub async fn validate_message(
+    c: MethodCall,
+    _: RemoteNodeSection,
+) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<ValidateMessageParamsAPI>()?;
+
+    let report = filecoin_signer::validation::validate_message(&params.transaction);
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: serde_json::to_value(&report)?,
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
 pub async fn verify_signature(
     c: MethodCall,
     _: RemoteNodeSection,
@@ -211,6 +477,134 @@ pub async fn verify_signature(
     Ok(so)
 }
 
+pub async fn aggregate_signatures(
+    c: MethodCall,
+    _: RemoteNodeSection,
+) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<AggregateSignaturesParamsAPI>()?;
+
+    let signatures = params
+        .signatures_hex
+        .into_iter()
+        .map(SignatureBLS::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let aggregated_signature = filecoin_signer::aggregate_signatures(&signatures)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(hex::encode(aggregated_signature.as_bytes())),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn sign_message(c: MethodCall, _: RemoteNodeSection) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<SignMessageParamsAPI>()?;
+
+    let message = hex::decode(&params.message_hex)?;
+    let private_key = PrivateKey::try_from(params.prvkey_base64)?;
+
+    let key = if params.bls {
+        filecoin_signer::key_recover_bls(&private_key, false)?
+    } else {
+        filecoin_signer::key_recover(&private_key, false)?
+    };
+
+    let prefix = params.prefix.map(String::into_bytes);
+    let signature = filecoin_signer::sign_message(&message, prefix.as_deref(), &key)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(hex::encode(signature.as_bytes())),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn verify_message(c: MethodCall, _: RemoteNodeSection) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<VerifyMessageParamsAPI>()?;
+
+    let signature = Signature::try_from(params.signature_hex)?;
+    let message = hex::decode(&params.message_hex)?;
+    let prefix = params.prefix.map(String::into_bytes);
+
+    let result =
+        filecoin_signer::verify_message(&signature, &message, prefix.as_deref(), &params.address)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(result),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn address_parse(c: MethodCall, _: RemoteNodeSection) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<AddressParamsAPI>()?;
+
+    let info = filecoin_signer::address::address_parse(&params.address)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: serde_json::to_value(&info)?,
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn address_validate(
+    c: MethodCall,
+    _: RemoteNodeSection,
+) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<AddressParamsAPI>()?;
+
+    let result = filecoin_signer::address::address_validate(&params.address);
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(result),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn address_to_network(
+    c: MethodCall,
+    _: RemoteNodeSection,
+) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<AddressToNetworkParamsAPI>()?;
+
+    let address = filecoin_signer::address::address_to_network(&params.address, params.testnet)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(address),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
+pub async fn actor_address(c: MethodCall, _: RemoteNodeSection) -> Result<Success, ServiceError> {
+    let params = c.params.parse::<ActorAddressParamsAPI>()?;
+
+    let address = filecoin_signer::address::actor_address(&params.creator, params.nonce)?;
+
+    let so = Success {
+        jsonrpc: Some(Version::V2),
+        result: Value::from(address),
+        id: c.id,
+    };
+
+    Ok(so)
+}
+
 pub async fn get_status(c: MethodCall, config: RemoteNodeSection) -> Result<Success, ServiceError> {
     let call_params = c.params.parse::<GetStatusParamsAPI>()?;
     let params = json!({"/": call_params.cid_message.to_string()});
@@ -243,10 +637,9 @@ pub async fn send_signed_tx(
     c: MethodCall,
     config: RemoteNodeSection,
 ) -> Result<Success, ServiceError> {
-    // Deserialize and back again to be sure is all valid
+    // Deserialize and convert to Lotus format to be sure is all valid
     let call_params = c.params.parse::<SendSignedTxParamsAPI>()?;
-    let signed_tx_json = serde_json::to_string(&call_params.signed_tx)?;
-    let signed_tx = serde_json::from_str(&signed_tx_json)?;
+    let signed_tx = serde_json::to_value(&LotusSignedMessage::try_from(&call_params.signed_tx)?)?;
 
     // send to remote node
     let result = client::send_signed_tx(&config.url, &config.jwt, signed_tx).await?;
@@ -266,7 +659,11 @@ pub async fn send_sign(c: MethodCall, config: RemoteNodeSection) -> Result<Succe
     let private_key = PrivateKey::try_from(params.prvkey_base64)?;
 
     // signed message
-    let signed_message = filecoin_signer::transaction_sign(&params.transaction, &private_key)?;
+    let signed_message = if params.strict {
+        filecoin_signer::transaction_sign_strict(&params.transaction, &private_key)?
+    } else {
+        filecoin_signer::transaction_sign(&params.transaction, &private_key)?
+    };
 
     let result = client::is_mainnet(&config.url, &config.jwt).await?;
 
@@ -282,7 +679,8 @@ pub async fn send_sign(c: MethodCall, config: RemoteNodeSection) -> Result<Succe
         }
     }
 
-    let signed_message_value = serde_json::to_value(&signed_message)?;
+    let signed_message_value =
+        serde_json::to_value(&LotusSignedMessage::try_from(&signed_message)?)?;
 
     // send to remote node
     let result = client::send_signed_tx(&config.url, &config.jwt, signed_message_value).await?;
@@ -34,6 +34,7 @@ pub async fn service_main() {
     let config = app_config();
     println!("Remote URL    : {}", &config.remote_node.url);
     println!("Local address : {}", &config.service.address);
+    println!("Keystore path : {}", &config.service.keystore_path);
 
     let addr: SocketAddr = config.service.address.parse().unwrap_or_else(|e| {
         println!("Address {} is invalid: {}", &config.service.address, e);
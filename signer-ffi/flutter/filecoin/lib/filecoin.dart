@@ -46,5 +46,9 @@ class Filecoin {
     filecoin
       .lookup<NativeFunction<Void Function(Pointer<Utf8>)>>("filecoin_signer_string_free")
       .asFunction();
+  static final void Function(Pointer<Utf8>) secretStringFree =
+    filecoin
+      .lookup<NativeFunction<Void Function(Pointer<Utf8>)>>("filecoin_signer_secret_string_free")
+      .asFunction();
 }
 
@@ -1,12 +1,13 @@
 use ffi_support::{call_with_result, ExternError};
 use filecoin_signer::ExtendedKey;
 
+// The returned string must be released with `filecoin_signer_secret_string_free`
 create_fn!(filecoin_signer_extended_key_private_key|Java_ch_zondax_FilecoinSigner_extendedKeyPrivateKey: (
     ek: &mut ExtendedKey,
     error: &mut ExternError
 ) -> str_ret_ty!(), |etc| {
     call_with_result(error, || -> Result<str_ret_ty!(), ExternError> {
-        create_string!(etc, hex::encode(&ek.private_key.0))
+        create_string!(etc, ek.private_key.export_hex())
     })
 });
 
@@ -19,6 +20,7 @@ create_fn!(filecoin_signer_extended_key_public_key|Java_ch_zondax_FilecoinSigner
     })
 });
 
+// Dropping the key zeroizes the private key
 create_fn_destructor!(
     ExtendedKey,
     filecoin_signer_extended_key_free | Java_ch_zondax_FilecoinSigner_extendedKeyFree
@@ -8,6 +8,8 @@ mod extended_key;
 
 use ffi_support::{call_with_result, ExternError};
 use filecoin_signer::{key_derive, ExtendedKey};
+#[cfg(not(feature = "with-jni"))]
+use zeroize::Zeroize;
 
 create_fn!(filecoin_signer_key_derive|Java_ch_zondax_FilecoinSigner_keyDerive: (
     mnemonic: str_arg_ty!(),
@@ -33,6 +35,18 @@ create_fn!(filecoin_signer_key_derive|Java_ch_zondax_FilecoinSigner_keyDerive: (
 #[cfg(not(feature = "with-jni"))]
 ffi_support::define_string_destructor!(filecoin_signer_string_free);
 
+/// Zeroize and free a string holding a secret (e.g. a private key)
+#[cfg(not(feature = "with-jni"))]
+#[no_mangle]
+pub extern "C" fn filecoin_signer_secret_string_free(s: *mut std::os::raw::c_char) {
+    ffi_support::abort_on_panic::with_abort_on_panic(|| {
+        if !s.is_null() {
+            let mut bytes = unsafe { std::ffi::CString::from_raw(s) }.into_bytes_with_nul();
+            bytes.zeroize();
+        }
+    });
+}
+
 #[cfg(feature = "with-jni")]
 fn get_string_ref(s: &std::ffi::CStr) -> &str {
     ffi_support::FfiStr::from_cstr(s).as_str()
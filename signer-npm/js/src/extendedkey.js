@@ -52,6 +52,19 @@ class ExtendedKey {
   get private_base64() {
     return this.privateKey.toString('base64')
   }
+
+  // Same explicit export methods as the wasm ExtendedKey
+  exportPrivateRaw() {
+    return this.private_raw
+  }
+
+  exportPrivateHexstring() {
+    return this.private_hexstring
+  }
+
+  exportPrivateBase64() {
+    return this.private_base64
+  }
 }
 
 module.exports = ExtendedKey
@@ -0,0 +1,132 @@
+use wasm_bindgen::prelude::*;
+
+use filecoin_signer::error::SignerError;
+use filecoin_signer::keystore::KeystoreStorage;
+
+use crate::{set_panic_hook, ExtendedKey};
+
+/// Keystore storage backed by JS callbacks
+///
+/// * `load(address)` returns the stored entry string or `undefined`
+/// * `store(address, entry)` persists the entry string
+/// * `list()` returns the array of stored addresses
+pub struct JsStorage {
+    load: js_sys::Function,
+    store: js_sys::Function,
+    list: js_sys::Function,
+}
+
+fn storage_error(e: JsValue) -> SignerError {
+    SignerError::GenericString(format!("Storage callback failed: {:?}", e))
+}
+
+impl KeystoreStorage for JsStorage {
+    fn load(&self, address: &str) -> Result<Option<String>, SignerError> {
+        let entry = self
+            .load
+            .call1(&JsValue::NULL, &JsValue::from(address))
+            .map_err(storage_error)?;
+
+        Ok(entry.as_string())
+    }
+
+    fn store(&mut self, address: &str, entry: &str) -> Result<(), SignerError> {
+        self.store
+            .call2(
+                &JsValue::NULL,
+                &JsValue::from(address),
+                &JsValue::from(entry),
+            )
+            .map_err(storage_error)?;
+
+        Ok(())
+    }
+
+    fn addresses(&self) -> Result<Vec<String>, SignerError> {
+        let addresses = self.list.call0(&JsValue::NULL).map_err(storage_error)?;
+
+        Ok(js_sys::Array::from(&addresses)
+            .iter()
+            .filter_map(|address| address.as_string())
+            .collect())
+    }
+}
+
+#[wasm_bindgen]
+pub struct Keystore(filecoin_signer::keystore::Keystore<JsStorage>);
+
+#[wasm_bindgen]
+impl Keystore {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        load: js_sys::Function,
+        store: js_sys::Function,
+        list: js_sys::Function,
+    ) -> Keystore {
+        set_panic_hook();
+
+        Keystore {
+            0: filecoin_signer::keystore::Keystore::new(JsStorage { load, store, list }),
+        }
+    }
+
+    pub fn create(
+        &mut self,
+        key: &ExtendedKey,
+        password: String,
+        mnemonic: Option<String>,
+    ) -> Result<(), JsValue> {
+        self.0
+            .create(&key.0, mnemonic.as_deref(), &password)
+            .map_err(|e| JsValue::from(format!("Error storing key: {}", e)))
+    }
+
+    pub fn unlock(&self, address: String, password: String) -> Result<ExtendedKey, JsValue> {
+        let unlocked_key = self
+            .0
+            .unlock(&address, &password)
+            .map_err(|e| JsValue::from(format!("Error unlocking key: {}", e)))?;
+
+        Ok(ExtendedKey {
+            0: unlocked_key.extended_key,
+        })
+    }
+
+    #[wasm_bindgen(js_name = unlockMnemonic)]
+    pub fn unlock_mnemonic(
+        &self,
+        address: String,
+        password: String,
+    ) -> Result<Option<String>, JsValue> {
+        let unlocked_key = self
+            .0
+            .unlock(&address, &password)
+            .map_err(|e| JsValue::from(format!("Error unlocking key: {}", e)))?;
+
+        Ok(unlocked_key
+            .mnemonic
+            .map(|mnemonic| mnemonic.export_phrase().to_string()))
+    }
+
+    #[wasm_bindgen(js_name = changePassword)]
+    pub fn change_password(
+        &mut self,
+        address: String,
+        old_password: String,
+        new_password: String,
+    ) -> Result<(), JsValue> {
+        self.0
+            .change_password(&address, &old_password, &new_password)
+            .map_err(|e| JsValue::from(format!("Error changing password: {}", e)))
+    }
+
+    pub fn list(&self) -> Result<JsValue, JsValue> {
+        let addresses = self
+            .0
+            .list()
+            .map_err(|e| JsValue::from(format!("Error listing keys: {}", e)))?;
+
+        JsValue::from_serde(&addresses)
+            .map_err(|e| JsValue::from(format!("Error listing keys: {}", e)))
+    }
+}
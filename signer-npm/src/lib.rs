@@ -3,11 +3,15 @@
 use std::convert::TryFrom;
 
 use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
 
-use filecoin_signer::api::{MessageParams, MessageTxAPI, UnsignedMessageAPI};
-use filecoin_signer::signature::Signature;
+use filecoin_signer::api::{MessageParams, MessageTxAPI, SignedMessageAPI, UnsignedMessageAPI};
+use filecoin_signer::bundle::{BundleMessage, SigningBundle};
+use filecoin_signer::signature::{Signature, SignatureBLS};
 use filecoin_signer::{CborBuffer, PrivateKey};
 
+mod keystore;
+mod transport;
 mod utils;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
@@ -37,35 +41,36 @@ impl ExtendedKey {
         self.0.public_key.to_vec()
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn private_raw(&self) -> Vec<u8> {
-        self.0.private_key.0.to_vec()
-    }
-
     #[wasm_bindgen(getter)]
     pub fn public_hexstring(&self) -> String {
         hex::encode(&self.public_raw())
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn private_hexstring(&self) -> String {
-        hex::encode(&self.private_raw())
-    }
-
     #[wasm_bindgen(getter)]
     pub fn public_base64(&self) -> String {
         base64::encode(&self.public_raw())
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn private_base64(&self) -> String {
-        base64::encode(&self.private_raw())
-    }
-
     #[wasm_bindgen(getter)]
     pub fn address(&self) -> String {
         self.0.address.clone()
     }
+
+    /// The private key is only exported on request, call `free()` to zeroize it once done
+    #[wasm_bindgen(js_name = exportPrivateRaw)]
+    pub fn export_private_raw(&self) -> Vec<u8> {
+        self.0.private_key.export_bytes().to_vec()
+    }
+
+    #[wasm_bindgen(js_name = exportPrivateHexstring)]
+    pub fn export_private_hexstring(&self) -> String {
+        self.0.private_key.export_hex()
+    }
+
+    #[wasm_bindgen(js_name = exportPrivateBase64)]
+    pub fn export_private_base64(&self) -> String {
+        self.0.private_key.export_base64()
+    }
 }
 
 fn extract_private_key(private_key_js: JsValue) -> Result<PrivateKey, JsValue> {
@@ -112,7 +117,7 @@ pub fn mnemonic_generate() -> Result<String, JsValue> {
     let mnemonic = filecoin_signer::key_generate_mnemonic()
         .map_err(|e| JsValue::from(format!("Error generating key: {}", e)))?;
 
-    Ok(mnemonic.0)
+    Ok(mnemonic.export_phrase().to_string())
 }
 
 #[wasm_bindgen(js_name = keyDerive)]
@@ -139,10 +144,13 @@ pub fn key_derive(
 pub fn key_derive_from_seed(seed: JsValue, path: String) -> Result<ExtendedKey, JsValue> {
     set_panic_hook();
 
-    let seed_bytes = extract_bytes(seed, "Seed must be a valid hexstring, base64 or a buffer")?;
+    let mut seed_bytes = extract_bytes(seed, "Seed must be a valid hexstring, base64 or a buffer")?;
 
-    let key_address = filecoin_signer::key_derive_from_seed(&seed_bytes, &path)
-        .map_err(|e| JsValue::from(format!("Error deriving key: {}", e)))?;
+    let key_address = filecoin_signer::key_derive_from_seed(&seed_bytes, &path);
+    seed_bytes.zeroize();
+
+    let key_address =
+        key_address.map_err(|e| JsValue::from(format!("Error deriving key: {}", e)))?;
 
     Ok(ExtendedKey { 0: key_address })
 }
@@ -171,6 +179,16 @@ pub fn key_recover_bls(private_key_js: JsValue, testnet: bool) -> Result<Extende
     Ok(ExtendedKey { 0: key_address })
 }
 
+#[wasm_bindgen(js_name = keyGenerateBLS)]
+pub fn key_generate_bls(testnet: bool) -> Result<ExtendedKey, JsValue> {
+    set_panic_hook();
+
+    let key_address = filecoin_signer::key_generate_bls(testnet)
+        .map_err(|e| JsValue::from(format!("Error generating key: {}", e)))?;
+
+    Ok(ExtendedKey { 0: key_address })
+}
+
 #[wasm_bindgen(js_name = transactionSerialize)]
 pub fn transaction_serialize(message: JsValue) -> Result<String, JsValue> {
     set_panic_hook();
@@ -235,6 +253,45 @@ pub fn transaction_sign(
     Ok(signed_message_js)
 }
 
+#[wasm_bindgen(js_name = transactionSignStrict)]
+pub fn transaction_sign_strict(
+    unsigned_tx_js: JsValue,
+    private_key_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let unsigned_message = unsigned_tx_js
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let private_key_bytes = extract_private_key(private_key_js)?;
+
+    let signed_message =
+        filecoin_signer::transaction_sign_strict(&unsigned_message, &private_key_bytes)
+            .map_err(|e| JsValue::from_str(format!("Error signing transaction: {}", e).as_str()))?;
+
+    let signed_message_js = JsValue::from_serde(&signed_message)
+        .map_err(|e| JsValue::from(format!("Error signing transaction: {}", e)))?;
+
+    Ok(signed_message_js)
+}
+
+#[wasm_bindgen(js_name = validateMessage)]
+pub fn validate_message(unsigned_tx_js: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let unsigned_message = unsigned_tx_js
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let report = filecoin_signer::validation::validate_message(&unsigned_message);
+
+    let report_js = JsValue::from_serde(&report)
+        .map_err(|e| JsValue::from(format!("Error converting validation report: {}", e)))?;
+
+    Ok(report_js)
+}
+
 #[wasm_bindgen(js_name = transactionSignLotus)]
 pub fn transaction_sign_lotus(
     unsigned_tx_js: JsValue,
@@ -257,6 +314,33 @@ pub fn transaction_sign_lotus(
     Ok(signed_message_lotus)
 }
 
+#[wasm_bindgen(js_name = messageToLotusJson)]
+pub fn message_to_lotus_json(message: JsValue) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let message_api: MessageTxAPI = message
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let json = filecoin_signer::lotus::message_to_json(&message_api)
+        .map_err(|e| JsValue::from(format!("Error converting to Lotus format: {}", e)))?;
+
+    Ok(json)
+}
+
+#[wasm_bindgen(js_name = messageFromLotusJson)]
+pub fn message_from_lotus_json(json: String) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let message_api = filecoin_signer::lotus::message_from_json(&json)
+        .map_err(|e| JsValue::from(format!("Error parsing Lotus message: {}", e)))?;
+
+    let message_js = JsValue::from_serde(&message_api)
+        .map_err(|e| JsValue::from(format!("Error converting message to json object: {}", e)))?;
+
+    Ok(message_js)
+}
+
 #[wasm_bindgen(js_name = transactionSignRaw)]
 pub fn transaction_sign_raw(
     unsigned_tx_js: JsValue,
@@ -299,6 +383,92 @@ pub fn verify_signature(signature_js: JsValue, message_js: JsValue) -> Result<bo
         .map_err(|e| JsValue::from_str(format!("Error verifying signature: {}", e).as_str()))
 }
 
+#[wasm_bindgen(js_name = aggregateSignatures)]
+pub fn aggregate_signatures(signatures_js: js_sys::Array) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let signatures = signatures_js
+        .iter()
+        .map(|signature_js| {
+            let signature_bytes = extract_bytes(
+                signature_js,
+                "Signature must be encoded as hexstring, base64 or a buffer",
+            )?;
+
+            SignatureBLS::try_from(signature_bytes).map_err(|e| JsValue::from(e.to_string()))
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+
+    let aggregated_signature = filecoin_signer::aggregate_signatures(&signatures)
+        .map_err(|e| JsValue::from(format!("Error aggregating signatures: {}", e)))?;
+
+    JsValue::from_serde(&aggregated_signature.as_bytes())
+        .map_err(|e| JsValue::from(format!("Error aggregating signatures: {}", e)))
+}
+
+fn extract_prefix(prefix: Option<String>) -> Option<Vec<u8>> {
+    prefix.map(|prefix| prefix.into_bytes())
+}
+
+#[wasm_bindgen(js_name = signMessage)]
+pub fn sign_message(
+    message_js: JsValue,
+    private_key_js: JsValue,
+    bls: bool,
+    prefix: Option<String>,
+) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let message_bytes = extract_bytes(
+        message_js,
+        "Message must be encoded as hexstring, base64 or a buffer",
+    )?;
+
+    let private_key = extract_private_key(private_key_js)?;
+
+    let key = if bls {
+        filecoin_signer::key_recover_bls(&private_key, false)
+    } else {
+        filecoin_signer::key_recover(&private_key, false)
+    }
+    .map_err(|e| JsValue::from(format!("Error signing message: {}", e)))?;
+
+    let prefix = extract_prefix(prefix);
+
+    let signature = filecoin_signer::sign_message(&message_bytes, prefix.as_deref(), &key)
+        .map_err(|e| JsValue::from(format!("Error signing message: {}", e)))?;
+
+    JsValue::from_serde(&signature.as_bytes())
+        .map_err(|e| JsValue::from(format!("Error signing message: {}", e)))
+}
+
+#[wasm_bindgen(js_name = verifyMessage)]
+pub fn verify_message(
+    signature_js: JsValue,
+    message_js: JsValue,
+    address: String,
+    prefix: Option<String>,
+) -> Result<bool, JsValue> {
+    set_panic_hook();
+
+    let signature_bytes = extract_bytes(
+        signature_js,
+        "Signature must be encoded as hexstring, base64 or a buffer",
+    )?;
+
+    let sig = Signature::try_from(signature_bytes).map_err(|e| JsValue::from(e.to_string()))?;
+
+    let message_bytes = extract_bytes(
+        message_js,
+        "Message must be encoded as hexstring, base64 or a buffer",
+    )?;
+
+    let prefix = extract_prefix(prefix);
+
+    filecoin_signer::verify_message(&sig, &message_bytes, prefix.as_deref(), &address)
+        .map_err(|e| JsValue::from(format!("Error verifying message: {}", e)))
+}
+
 fn signer_value_to_string(address_value: JsValue) -> Result<String, JsValue> {
     let address = address_value.as_string();
 
@@ -953,6 +1123,28 @@ pub fn deserialize_params(
     Ok(params_value)
 }
 
+#[wasm_bindgen(js_name = deserializeReturn)]
+pub fn deserialize_return(
+    return_base64: String,
+    actor_type: String,
+    method: u32,
+) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let message_return =
+        filecoin_signer::deserialize_return(return_base64, actor_type, method as u64)
+            .map_err(|e| JsValue::from(format!("Error deserializing return value: {}", e)))?;
+
+    let return_value = JsValue::from_serde(&message_return).map_err(|e| {
+        JsValue::from(format!(
+            "Error converting return value to json object: {}",
+            e
+        ))
+    })?;
+
+    Ok(return_value)
+}
+
 #[wasm_bindgen(js_name = deserializeConstructorParams)]
 pub fn deserialize_constructor_params(
     params_base64: String,
@@ -999,3 +1191,212 @@ pub fn get_cid(message: JsValue) -> Result<String, JsValue> {
 
     Ok(result)
 }
+
+#[wasm_bindgen(js_name = getCidBytes)]
+pub fn get_cid_bytes(message: JsValue) -> Result<Vec<u8>, JsValue> {
+    set_panic_hook();
+
+    let message_api: MessageTxAPI = message
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let result = filecoin_signer::get_cid_bytes(&message_api)
+        .map_err(|e| JsValue::from(format!("Error getting the cid: {}", e)))?;
+
+    Ok(result)
+}
+
+#[wasm_bindgen(js_name = signedMessageSerialize)]
+pub fn signed_message_serialize(signed_message: JsValue) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let signed_message: SignedMessageAPI = signed_message
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let cbor_buffer = filecoin_signer::signed_message_serialize(&signed_message)
+        .map_err(|e| JsValue::from(format!("Error converting to CBOR: {}", e)))?;
+
+    Ok(hex::encode(&cbor_buffer.0))
+}
+
+#[wasm_bindgen(js_name = signedMessageParse)]
+pub fn signed_message_parse(cbor_js: JsValue, testnet: bool) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let cbor_bytes = extract_bytes(
+        cbor_js,
+        "CBOR message must be encoded as hexstring, base64 or a buffer",
+    )?;
+
+    let signed_message = filecoin_signer::signed_message_parse(&CborBuffer(cbor_bytes), testnet)
+        .map_err(|e| JsValue::from(e.to_string()))?;
+
+    let tx = JsValue::from_serde(&signed_message).map_err(|e| JsValue::from(e.to_string()))?;
+
+    Ok(tx)
+}
+
+#[wasm_bindgen(js_name = addressParse)]
+pub fn address_parse(address: String) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let info = filecoin_signer::address::address_parse(&address)
+        .map_err(|e| JsValue::from(format!("Error parsing address: {}", e)))?;
+
+    let info_js = JsValue::from_serde(&info)
+        .map_err(|e| JsValue::from(format!("Error converting address to json object: {}", e)))?;
+
+    Ok(info_js)
+}
+
+#[wasm_bindgen(js_name = addressValidate)]
+pub fn address_validate(address: String) -> bool {
+    set_panic_hook();
+
+    filecoin_signer::address::address_validate(&address)
+}
+
+#[wasm_bindgen(js_name = addressToNetwork)]
+pub fn address_to_network(address: String, testnet: bool) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let address = filecoin_signer::address::address_to_network(&address, testnet)
+        .map_err(|e| JsValue::from(format!("Error converting address: {}", e)))?;
+
+    Ok(address)
+}
+
+#[wasm_bindgen(js_name = actorAddress)]
+pub fn actor_address(creator: String, nonce: u32) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let address = filecoin_signer::address::actor_address(&creator, nonce as u64)
+        .map_err(|e| JsValue::from(format!("Error computing actor address: {}", e)))?;
+
+    Ok(address)
+}
+
+#[wasm_bindgen(js_name = ethAddressToF410)]
+pub fn eth_address_to_f410(eth_address: String, testnet: bool) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let address = filecoin_signer::address::eth_address_to_f410(&eth_address, testnet)
+        .map_err(|e| JsValue::from(format!("Error converting Ethereum address: {}", e)))?;
+
+    Ok(address)
+}
+
+#[wasm_bindgen(js_name = f410ToEthAddress)]
+pub fn f410_to_eth_address(address: String) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let eth_address = filecoin_signer::address::f410_to_eth_address(&address)
+        .map_err(|e| JsValue::from(format!("Error converting f410 address: {}", e)))?;
+
+    Ok(eth_address)
+}
+
+#[wasm_bindgen(js_name = parseTokenAmount)]
+pub fn parse_token_amount(amount: String) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let amount = amount
+        .parse::<filecoin_signer::token::TokenAmount>()
+        .map_err(|e| JsValue::from(format!("Error parsing token amount: {}", e)))?;
+
+    Ok(amount.to_atto_string())
+}
+
+#[wasm_bindgen(js_name = formatTokenAmount)]
+pub fn format_token_amount(amount: String, unit: String) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let amount = amount
+        .parse::<filecoin_signer::token::TokenAmount>()
+        .map_err(|e| JsValue::from(format!("Error parsing token amount: {}", e)))?;
+    let unit = unit
+        .parse::<filecoin_signer::token::TokenUnit>()
+        .map_err(|e| JsValue::from(format!("Error parsing token unit: {}", e)))?;
+
+    Ok(amount.format(unit))
+}
+
+#[wasm_bindgen(js_name = bundleCreate)]
+pub fn bundle_create(
+    messages: JsValue,
+    testnet: bool,
+    max_fee: Option<String>,
+) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let messages: Vec<BundleMessage> = messages
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let max_fee = max_fee
+        .map(|max_fee| max_fee.parse::<filecoin_signer::token::TokenAmount>())
+        .transpose()
+        .map_err(|e| JsValue::from(format!("Error parsing max fee: {}", e)))?;
+
+    let bundle = filecoin_signer::bundle::bundle_create(messages, testnet, max_fee)
+        .map_err(|e| JsValue::from(format!("Error creating bundle: {}", e)))?;
+
+    let bundle_js = JsValue::from_serde(&bundle)
+        .map_err(|e| JsValue::from(format!("Error converting bundle to json object: {}", e)))?;
+
+    Ok(bundle_js)
+}
+
+#[wasm_bindgen(js_name = bundleInspect)]
+pub fn bundle_inspect(bundle: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let bundle: SigningBundle = bundle
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let summary = filecoin_signer::bundle::bundle_inspect(&bundle)
+        .map_err(|e| JsValue::from(format!("Error inspecting bundle: {}", e)))?;
+
+    let summary_js = JsValue::from_serde(&summary)
+        .map_err(|e| JsValue::from(format!("Error converting summary to json object: {}", e)))?;
+
+    Ok(summary_js)
+}
+
+#[wasm_bindgen(js_name = bundleSign)]
+pub fn bundle_sign(bundle: JsValue, private_key_js: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let mut bundle: SigningBundle = bundle
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let private_key = extract_private_key(private_key_js)?;
+
+    filecoin_signer::bundle::bundle_sign(&mut bundle, &private_key)
+        .map_err(|e| JsValue::from(format!("Error signing bundle: {}", e)))?;
+
+    let bundle_js = JsValue::from_serde(&bundle)
+        .map_err(|e| JsValue::from(format!("Error converting bundle to json object: {}", e)))?;
+
+    Ok(bundle_js)
+}
+
+#[wasm_bindgen(js_name = bundleFinalize)]
+pub fn bundle_finalize(bundle: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let bundle: SigningBundle = bundle
+        .into_serde()
+        .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+    let signed_messages = filecoin_signer::bundle::bundle_finalize(&bundle)
+        .map_err(|e| JsValue::from(format!("Error finalizing bundle: {}", e)))?;
+
+    let signed_messages_js = JsValue::from_serde(&signed_messages)
+        .map_err(|e| JsValue::from(format!("Error converting messages to json object: {}", e)))?;
+
+    Ok(signed_messages_js)
+}
@@ -0,0 +1,99 @@
+use wasm_bindgen::prelude::*;
+
+use filecoin_signer::api::MessageTxAPI;
+use filecoin_signer::transport::{FountainDecoder, FountainEncoder, FountainPart};
+
+use crate::set_panic_hook;
+
+/// Animated QR encoder: an endless stream of parts for a signed or unsigned message
+#[wasm_bindgen]
+pub struct QrEncoder(FountainEncoder);
+
+#[wasm_bindgen]
+impl QrEncoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(message: JsValue, max_fragment_len: u32) -> Result<QrEncoder, JsValue> {
+        set_panic_hook();
+
+        let message: MessageTxAPI = message
+            .into_serde()
+            .map_err(|e| JsValue::from(format!("Error parsing parameters: {}", e)))?;
+
+        let bytes = filecoin_signer::transport::message_to_bytes(&message)
+            .map_err(|e| JsValue::from(format!("Error converting to CBOR: {}", e)))?;
+
+        let encoder = FountainEncoder::new(&bytes, max_fragment_len as usize)
+            .map_err(|e| JsValue::from(format!("Error encoding message: {}", e)))?;
+
+        Ok(QrEncoder { 0: encoder })
+    }
+
+    /// Number of parts needed at least to rebuild the message
+    #[wasm_bindgen(js_name = seqLen)]
+    pub fn seq_len(&self) -> u32 {
+        self.0.seq_len() as u32
+    }
+
+    #[wasm_bindgen(js_name = nextPart)]
+    pub fn next_part(&mut self) -> Result<String, JsValue> {
+        self.0
+            .next_part()
+            .to_qr_string()
+            .map_err(|e| JsValue::from(format!("Error encoding part: {}", e)))
+    }
+}
+
+/// Animated QR decoder: feed it the scanned parts, in any order, until it is complete
+#[wasm_bindgen]
+pub struct QrDecoder(FountainDecoder);
+
+#[wasm_bindgen]
+impl QrDecoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> QrDecoder {
+        set_panic_hook();
+
+        QrDecoder {
+            0: FountainDecoder::new(),
+        }
+    }
+
+    /// Add a scanned part, return `true` once the message is complete
+    pub fn receive(&mut self, part: String) -> Result<bool, JsValue> {
+        let part = FountainPart::from_qr_string(&part)
+            .map_err(|e| JsValue::from(format!("Error parsing part: {}", e)))?;
+
+        self.0
+            .receive(&part)
+            .map_err(|e| JsValue::from(format!("Error decoding part: {}", e)))
+    }
+
+    #[wasm_bindgen(js_name = isComplete)]
+    pub fn is_complete(&self) -> bool {
+        self.0.is_complete()
+    }
+
+    /// Fraction of the message received (between 0 and 1)
+    pub fn progress(&self) -> f64 {
+        self.0.progress()
+    }
+
+    /// The message, once complete (checksum verified)
+    pub fn message(&self, testnet: bool) -> Result<JsValue, JsValue> {
+        let bytes = self
+            .0
+            .message()
+            .map_err(|e| JsValue::from(format!("Error decoding message: {}", e)))?;
+
+        let message = filecoin_signer::transport::message_from_bytes(&bytes, testnet)
+            .map_err(|e| JsValue::from(e.to_string()))?;
+
+        JsValue::from_serde(&message).map_err(|e| JsValue::from(e.to_string()))
+    }
+}
+
+impl Default for QrDecoder {
+    fn default() -> Self {
+        QrDecoder::new()
+    }
+}
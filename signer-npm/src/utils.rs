@@ -11,6 +11,8 @@ extern "C" {
     fn from(buffer_array: &[u8]) -> Buffer;
 }
 
+/// Lotus format returned by `transactionSignLotus`, kept as is for compatibility (see
+/// `messageToLotusJson` for the full format with `Version` and `CID`)
 pub fn convert_to_lotus_signed_message(
     signed_message: SignedMessageAPI,
 ) -> Result<String, JsValue> {
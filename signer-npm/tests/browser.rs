@@ -34,7 +34,7 @@ fn key_derive() {
     );
 
     assert_eq!(
-        answer.private_hexstring(),
+        answer.export_private_hexstring(),
         "80c56e752ffdd06e3e0d9516e662e7ba883982404045a2c2d4cbe7c87e6c66fe"
     );
 
@@ -0,0 +1,332 @@
+use std::str::FromStr;
+
+use forest_address::{Address, Network, Protocol};
+use forest_encoding::to_vec;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+pub use extras::address::{AnyAddress, DelegatedAddress, DELEGATED_PROTOCOL};
+
+use crate::error::SignerError;
+
+/// Namespace of the Ethereum Address Manager actor (`f410` addresses)
+pub const EAM_NAMESPACE: u64 = 10;
+
+/// Ethereum address size in bytes
+pub const ETH_ADDRESS_SIZE: usize = 20;
+
+/// Protocol and payload of an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressInfo {
+    /// `true` for a testnet (`t`) address, `false` for mainnet (`f`)
+    pub testnet: bool,
+    /// Address protocol (0: ID, 1: secp256k1, 2: actor, 3: BLS, 4: delegated)
+    pub protocol: u8,
+    /// Hex encoded payload
+    pub payload: String,
+}
+
+fn network(testnet: bool) -> Network {
+    if testnet {
+        Network::Testnet
+    } else {
+        Network::Mainnet
+    }
+}
+
+/// Parse an address string and return its network, protocol and payload.
+///
+/// # Arguments
+///
+/// * `address` - a `f` (mainnet) or `t` (testnet) address string
+///
+pub fn address_parse(address: &str) -> Result<AddressInfo, SignerError> {
+    let address = AnyAddress::from_str(address)?;
+
+    Ok(AddressInfo {
+        testnet: address.network() == Network::Testnet,
+        protocol: address.protocol(),
+        payload: hex::encode(address.payload_bytes()),
+    })
+}
+
+/// Return `true` if the string is a valid address (network prefix, protocol, payload and checksum).
+///
+/// # Arguments
+///
+/// * `address` - the address string to validate
+///
+pub fn address_validate(address: &str) -> bool {
+    AnyAddress::from_str(address).is_ok()
+}
+
+/// Convert an address to the given network (e.g `f1...` into `t1...`).
+///
+/// # Arguments
+///
+/// * `address` - a `f` (mainnet) or `t` (testnet) address string
+/// * `testnet` - specify the network, `true` if testnet else `false` for mainnet
+///
+pub fn address_to_network(address: &str, testnet: bool) -> Result<String, SignerError> {
+    let mut address = AnyAddress::from_str(address)?;
+    address.set_network(network(testnet));
+
+    Ok(address.to_string())
+}
+
+/// Compute the actor (`f2`) address of the actor created by a message, e.g the multisig wallet
+/// built by `create_multisig` or the payment channel built by `create_pymtchan`.
+/// The address is the hash of the creator address, the message nonce and the number of actors
+/// already created by the message (always `0` for these messages). It is returned on the network
+/// of the creator address.
+///
+/// # Arguments
+///
+/// * `creator` - the `from` address of the message; must be a secp256k1, BLS or delegated address
+/// * `nonce` - the nonce of the message
+///
+pub fn actor_address(creator: &str, nonce: u64) -> Result<String, SignerError> {
+    actor_address_with_index(creator, nonce, 0)
+}
+
+/// Compute the actor (`f2`) address of the `index`-th actor created by a message.
+///
+/// # Arguments
+///
+/// * `creator` - the `from` address of the message; must be a secp256k1, BLS or delegated address
+/// * `nonce` - the nonce of the message
+/// * `index` - the number of actors created by the message before this one
+///
+pub fn actor_address_with_index(
+    creator: &str,
+    nonce: u64,
+    index: u64,
+) -> Result<String, SignerError> {
+    let creator = AnyAddress::from_str(creator)?;
+
+    let protocol = creator.protocol();
+    if protocol != Protocol::Secp256k1 as u8
+        && protocol != Protocol::BLS as u8
+        && protocol != DELEGATED_PROTOCOL
+    {
+        return Err(SignerError::GenericString(
+            "Creator must be a secp256k1, BLS or delegated address".to_string(),
+        ));
+    }
+
+    let mut data = to_vec(&creator)?;
+    data.extend_from_slice(&nonce.to_be_bytes());
+    data.extend_from_slice(&index.to_be_bytes());
+
+    let mut address = Address::new_actor(&data);
+    address.set_network(creator.network());
+
+    Ok(address.to_string())
+}
+
+/// Return the Ethereum address of a secp256k1 public key (compressed or uncompressed).
+/// The address is the last 20 bytes of the keccak256 hash of the uncompressed public key.
+pub fn eth_address_from_public_key(
+    public_key: &[u8],
+) -> Result<[u8; ETH_ADDRESS_SIZE], SignerError> {
+    let public_key = secp256k1::PublicKey::parse_slice(public_key, None)?.serialize();
+
+    let hash = Keccak256::digest(&public_key[1..]);
+
+    let mut eth_address = [0u8; ETH_ADDRESS_SIZE];
+    eth_address.copy_from_slice(&hash[hash.len() - ETH_ADDRESS_SIZE..]);
+
+    Ok(eth_address)
+}
+
+/// Convert a `0x` Ethereum address into a `f410` address.
+///
+/// # Arguments
+///
+/// * `eth_address` - the hex encoded Ethereum address (with or without `0x` prefix)
+/// * `testnet` - specify the network, `true` if testnet else `false` for mainnet
+///
+pub fn eth_address_to_f410(eth_address: &str, testnet: bool) -> Result<String, SignerError> {
+    let eth_address = eth_address
+        .strip_prefix("0x")
+        .or_else(|| eth_address.strip_prefix("0X"))
+        .unwrap_or(eth_address);
+    let subaddress = hex::decode(eth_address)?;
+
+    if subaddress.len() != ETH_ADDRESS_SIZE {
+        return Err(SignerError::GenericString(format!(
+            "Invalid Ethereum address length {} (should be {} bytes)",
+            subaddress.len(),
+            ETH_ADDRESS_SIZE
+        )));
+    }
+
+    let mut address = DelegatedAddress::new(EAM_NAMESPACE, &subaddress)?;
+    address.set_network(network(testnet));
+
+    Ok(address.to_string())
+}
+
+/// Convert a `f410` address into a `0x` Ethereum address (lowercase hex).
+///
+/// # Arguments
+///
+/// * `address` - a `f410` (or `t410`) address
+///
+pub fn f410_to_eth_address(address: &str) -> Result<String, SignerError> {
+    let address = DelegatedAddress::from_str(address)?;
+
+    if address.namespace() != EAM_NAMESPACE || address.subaddress().len() != ETH_ADDRESS_SIZE {
+        return Err(SignerError::GenericString(
+            "Not an Ethereum (f410) address".to_string(),
+        ));
+    }
+
+    Ok(format!("0x{}", hex::encode(address.subaddress())))
+}
+
+/// Derive the `f410` address of a secp256k1 public key.
+///
+/// # Arguments
+///
+/// * `public_key` - a secp256k1 public key (compressed or uncompressed)
+/// * `testnet` - specify the network, `true` if testnet else `false` for mainnet
+///
+pub fn f410_address_from_public_key(
+    public_key: &[u8],
+    testnet: bool,
+) -> Result<String, SignerError> {
+    let eth_address = eth_address_from_public_key(public_key)?;
+
+    let mut address = DelegatedAddress::new(EAM_NAMESPACE, &eth_address)?;
+    address.set_network(network(testnet));
+
+    Ok(address.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const F410_ADDRESS: &str = "f410f2oekwcmo2pueydmaq53eic2i62crtbeyuzx2gmy";
+    const ETH_ADDRESS: &str = "0xd388ab098ed3e84c0d808776440b48f685198498";
+
+    #[test]
+    fn delegated_address_string_roundtrip() {
+        let address = DelegatedAddress::from_str(F410_ADDRESS).unwrap();
+
+        assert_eq!(address.namespace(), EAM_NAMESPACE);
+        assert_eq!(address.to_string(), F410_ADDRESS);
+
+        // Decoded addresses are on the `forest_address` default network
+        let mut decoded = DelegatedAddress::from_bytes(&address.to_bytes()).unwrap();
+        assert_eq!(decoded.network(), Address::new_id(0).network());
+        assert_eq!(decoded.set_network(Network::Mainnet), &address);
+        assert_eq!(
+            AnyAddress::from_str(F410_ADDRESS).unwrap(),
+            AnyAddress::Delegated(address)
+        );
+    }
+
+    #[test]
+    fn delegated_address_invalid() {
+        // bad checksum
+        assert!(
+            DelegatedAddress::from_str("f410f2oekwcmo2pueydmaq53eic2i62crtbeyuzx2gma").is_err()
+        );
+        // uppercase payload
+        assert!(
+            DelegatedAddress::from_str("f410F2OEKWCMO2PUEYDMAQ53EIC2I62CRTBEYUZX2GMY").is_err()
+        );
+        // missing namespace
+        assert!(DelegatedAddress::from_str("f4f2oekwcmo2pueydmaq53eic2i62crtbeyuzx2gmy").is_err());
+        // sub-address too long
+        assert!(DelegatedAddress::new(EAM_NAMESPACE, &[0u8; 55]).is_err());
+    }
+
+    #[test]
+    fn address_utilities() {
+        let info = address_parse("t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba").unwrap();
+        assert!(info.testnet);
+        assert_eq!(info.protocol, 1);
+        assert_eq!(info.payload.len(), 40);
+
+        let info = address_parse("f01234").unwrap();
+        assert!(!info.testnet);
+        assert_eq!(info.protocol, 0);
+        assert_eq!(info.payload, "d209");
+
+        assert_eq!(
+            address_parse(F410_ADDRESS).unwrap().protocol,
+            DELEGATED_PROTOCOL
+        );
+
+        assert!(address_validate("f01234"));
+        assert!(address_validate(F410_ADDRESS));
+        assert!(!address_validate(
+            "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epyba"
+        ));
+        assert!(!address_validate("x01234"));
+        assert!(!address_validate(""));
+
+        assert_eq!(
+            address_to_network("t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba", false).unwrap(),
+            "f1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba"
+        );
+        assert_eq!(
+            address_to_network(F410_ADDRESS, true).unwrap(),
+            format!("t{}", &F410_ADDRESS[1..])
+        );
+    }
+
+    #[test]
+    fn actor_address_from_creator() {
+        let creator = "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba";
+
+        assert_eq!(
+            actor_address(creator, 0).unwrap(),
+            "t2h6o4uvzsksf3yi2ri2uu7eqvhqkcp7axmg3mski"
+        );
+        assert_eq!(
+            actor_address(creator, 1).unwrap(),
+            "t2nzl23ysfwo53kl5syolbeh6j43sxx4hfmdhm56i"
+        );
+        assert_eq!(
+            actor_address(&address_to_network(creator, false).unwrap(), 7).unwrap(),
+            "f2jprf4qryvm2w3lrlvlxyhmi765ytmwzsza4dxua"
+        );
+
+        // Payment channels created on a Lotus devnet (`test_vectors/payment_channel.json`
+        // and `test_vectors/voucher.json`)
+        assert_eq!(
+            actor_address("t1gsu6clgzpcrjxclicnsva5bty3r65hnkqpd4jaq", 0).unwrap(),
+            "t2oajfrgjjllncvbxx4shzbxy3nnegsrnnk3tq2tq"
+        );
+        assert_eq!(
+            actor_address("t137sjdbgunloi7couiy4l5nc7pd6k2jmq32vizpy", 3).unwrap(),
+            "t24acjqhdetck7irsvmn2p6jpuwnouzjxuoa22rva"
+        );
+        assert_eq!(
+            actor_address("f137sjdbgunloi7couiy4l5nc7pd6k2jmq32vizpy", 3).unwrap(),
+            "f24acjqhdetck7irsvmn2p6jpuwnouzjxuoa22rva"
+        );
+
+        assert!(actor_address("t01234", 0).is_err());
+    }
+
+    #[test]
+    fn eth_address_conversion() {
+        assert_eq!(
+            eth_address_to_f410(ETH_ADDRESS, false).unwrap(),
+            F410_ADDRESS
+        );
+        assert_eq!(
+            eth_address_to_f410(&ETH_ADDRESS[2..], true).unwrap(),
+            format!("t{}", &F410_ADDRESS[1..])
+        );
+        assert_eq!(f410_to_eth_address(F410_ADDRESS).unwrap(), ETH_ADDRESS);
+
+        assert!(eth_address_to_f410("0xd388ab098ed3e84c0d80877644", false).is_err());
+        assert!(f410_to_eth_address("f01").is_err());
+    }
+}
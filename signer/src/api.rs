@@ -2,14 +2,19 @@ use std::convert::{TryFrom, TryInto};
 use std::str::FromStr;
 
 use forest_address::{Address, Network};
-use forest_cid::{multihash::MultihashDigest, Cid, Code::Identity};
+use forest_cid::{
+    multihash::MultihashDigest,
+    Cid,
+    Code::{Blake2b256, Identity},
+};
 use forest_crypto::signature;
+use forest_encoding::tuple::*;
 use forest_message::{Message, SignedMessage, UnsignedMessage};
 use forest_vm::Serialized;
-use num_bigint_chainsafe::BigInt;
+use num_bigint_chainsafe::{bigint_ser, BigInt};
 use serde::{Deserialize, Serialize, Serializer};
 
-use extras::{multisig, paych, ExecParams};
+use extras::{address::AnyAddress, multisig, paych, ExecParams, ExecReturn};
 
 use crate::error::SignerError;
 use crate::signature::Signature;
@@ -17,6 +22,7 @@ use crate::signature::Signature;
 pub enum SigTypes {
     SigTypeSecp256k1 = 0x01,
     SigTypeBLS = 0x02,
+    SigTypeDelegated = 0x03,
 }
 
 #[cfg_attr(feature = "with-arbitrary", derive(arbitrary::Arbitrary))]
@@ -128,6 +134,25 @@ impl Into<ExecParamsAPI> for ExecParams {
     }
 }
 
+/// Init actor `Exec` return: addresses of the created actor
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExecReturnAPI {
+    #[serde(alias = "IDAddress")]
+    pub id_address: String,
+    #[serde(alias = "RobustAddress")]
+    pub robust_address: String,
+}
+
+impl From<ExecReturn> for ExecReturnAPI {
+    fn from(exec_return: ExecReturn) -> ExecReturnAPI {
+        ExecReturnAPI {
+            id_address: exec_return.id_address.to_string(),
+            robust_address: exec_return.robust_address.to_string(),
+        }
+    }
+}
+
 #[cfg_attr(feature = "with-arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -152,7 +177,7 @@ impl TryFrom<ProposeParamsMultisig> for multisig::ProposeParams {
             .map_err(|err| SignerError::GenericString(err.to_string()))?;
 
         Ok(multisig::ProposeParams {
-            to: Address::from_str(&propose_params.to)?,
+            to: AnyAddress::from_str(&propose_params.to)?,
             value: BigInt::from_str(&propose_params.value)?,
             method: propose_params.method,
             params: forest_vm::Serialized::new(params),
@@ -171,6 +196,32 @@ impl Into<ProposeParamsMultisig> for multisig::ProposeParams {
     }
 }
 
+/// Multisig `Propose` return
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposeReturnMultisig {
+    #[serde(alias = "TxnID")]
+    pub txn_id: i64,
+    #[serde(alias = "Applied")]
+    pub applied: bool,
+    #[serde(alias = "Code")]
+    pub code: i64,
+    /// Base64 encoded return value of the applied transaction
+    #[serde(alias = "Ret")]
+    pub ret: String,
+}
+
+impl From<multisig::ProposeReturn> for ProposeReturnMultisig {
+    fn from(propose_return: multisig::ProposeReturn) -> ProposeReturnMultisig {
+        ProposeReturnMultisig {
+            txn_id: propose_return.txn_id.0,
+            applied: propose_return.applied,
+            code: propose_return.code,
+            ret: base64::encode(propose_return.ret.bytes()),
+        }
+    }
+}
+
 /// Proposal data
 #[cfg_attr(feature = "with-arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -199,7 +250,7 @@ impl TryFrom<PropoposalHashDataParamsMultisig> for multisig::ProposalHashData {
 
         Ok(multisig::ProposalHashData {
             requester: Address::from_str(&proposal_params.requester)?,
-            to: Address::from_str(&proposal_params.to)?,
+            to: AnyAddress::from_str(&proposal_params.to)?,
             value: BigInt::from_str(&proposal_params.value)?,
             method: proposal_params.method,
             params: forest_vm::Serialized::new(params),
@@ -445,7 +496,7 @@ impl TryFrom<PaymentChannelCreateParams> for paych::ConstructorParams {
     ) -> Result<paych::ConstructorParams, Self::Error> {
         Ok(paych::ConstructorParams {
             from: Address::from_str(&params.from)?,
-            to: Address::from_str(&params.to)?,
+            to: AnyAddress::from_str(&params.to)?,
         })
     }
 }
@@ -545,6 +596,14 @@ pub enum MessageParams {
     LockBalanceMultisigParams(LockBalanceMultisigParams),
 }
 
+/// Decoded return value of a message (`MessageReceipt.Return`)
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum MessageReturn {
+    ExecReturn(ExecReturnAPI),
+    ProposeReturnMultisig(ProposeReturnMultisig),
+}
+
 impl MessageParams {
     pub fn serialize(self) -> Result<Serialized, SignerError> {
         let params_serialized = match self {
@@ -727,6 +786,192 @@ pub struct MessageTxNetwork {
     pub testnet: bool,
 }
 
+/// Unsigned message accepting any address protocol, including delegated (`f4`) addresses that
+/// `forest_message` can't represent. Serialized exactly like a filecoin `UnsignedMessage`.
+#[derive(Debug, Clone, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct AnyUnsignedMessage {
+    pub version: i64,
+    pub to: AnyAddress,
+    pub from: AnyAddress,
+    pub sequence: u64,
+    #[serde(with = "bigint_ser")]
+    pub value: BigInt,
+    pub gas_limit: i64,
+    #[serde(with = "bigint_ser")]
+    pub gas_fee_cap: BigInt,
+    #[serde(with = "bigint_ser")]
+    pub gas_premium: BigInt,
+    pub method_num: u64,
+    pub params: Serialized,
+}
+
+impl AnyUnsignedMessage {
+    /// CID of the message (blake2b-256 of the CBOR encoded message)
+    pub fn cid(&self) -> Result<Cid, SignerError> {
+        let message_cbor = forest_encoding::to_vec(self)?;
+
+        Ok(Cid::new_v1(
+            forest_cid::DAG_CBOR,
+            Blake2b256.digest(&message_cbor),
+        ))
+    }
+
+    /// Bytes to sign (the message CID bytes)
+    pub fn to_signing_bytes(&self) -> Result<Vec<u8>, SignerError> {
+        Ok(self.cid()?.to_bytes())
+    }
+
+    pub fn set_network(&mut self, network: Network) -> &mut Self {
+        self.to.set_network(network);
+        self.from.set_network(network);
+        self
+    }
+}
+
+/// Signature of any type (secp256k1, BLS or delegated), serialized as on chain (`type || data`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnySignature {
+    pub sig_type: u8,
+    pub data: Vec<u8>,
+}
+
+impl AnySignature {
+    pub fn new(sig_type: u8, data: Vec<u8>) -> Result<Self, SignerError> {
+        if sig_type != SigTypes::SigTypeSecp256k1 as u8
+            && sig_type != SigTypes::SigTypeBLS as u8
+            && sig_type != SigTypes::SigTypeDelegated as u8
+        {
+            return Err(SignerError::GenericString(format!(
+                "Unknown signature type {}",
+                sig_type
+            )));
+        }
+
+        Ok(AnySignature { sig_type, data })
+    }
+}
+
+impl Serialize for AnySignature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut bytes = Vec::with_capacity(self.data.len() + 1);
+        bytes.push(self.sig_type);
+        bytes.extend_from_slice(&self.data);
+        serde_bytes::Serialize::serialize(&bytes, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AnySignature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let bytes: Vec<u8> = serde_bytes::Deserialize::deserialize(deserializer)?;
+        match bytes.split_first() {
+            Some((sig_type, data)) => {
+                AnySignature::new(*sig_type, data.to_vec()).map_err(serde::de::Error::custom)
+            }
+            None => Err(serde::de::Error::custom("Empty signature")),
+        }
+    }
+}
+
+impl TryFrom<&SignatureAPI> for AnySignature {
+    type Error = SignerError;
+
+    fn try_from(sig: &SignatureAPI) -> Result<AnySignature, Self::Error> {
+        AnySignature::new(sig.sig_type, sig.data.clone())
+    }
+}
+
+impl From<AnySignature> for SignatureAPI {
+    fn from(sig: AnySignature) -> SignatureAPI {
+        SignatureAPI {
+            sig_type: sig.sig_type,
+            data: sig.data,
+        }
+    }
+}
+
+/// Signed message accepting any address protocol and signature type. Serialized exactly like a
+/// filecoin `SignedMessage`.
+#[derive(Debug, Clone, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct AnySignedMessage {
+    pub message: AnyUnsignedMessage,
+    pub signature: AnySignature,
+}
+
+impl AnySignedMessage {
+    /// CID of the signed message. As on chain, the CID of a BLS signed message is the CID of the
+    /// unsigned message (BLS signatures are aggregated in the block).
+    pub fn cid(&self) -> Result<Cid, SignerError> {
+        if self.signature.sig_type == SigTypes::SigTypeBLS as u8 {
+            return self.message.cid();
+        }
+
+        let signed_message_cbor = forest_encoding::to_vec(self)?;
+
+        Ok(Cid::new_v1(
+            forest_cid::DAG_CBOR,
+            Blake2b256.digest(&signed_message_cbor),
+        ))
+    }
+}
+
+impl TryFrom<&SignedMessageAPI> for AnySignedMessage {
+    type Error = SignerError;
+
+    fn try_from(signed_message: &SignedMessageAPI) -> Result<AnySignedMessage, Self::Error> {
+        Ok(AnySignedMessage {
+            message: AnyUnsignedMessage::try_from(&signed_message.message)?,
+            signature: AnySignature::try_from(&signed_message.signature)?,
+        })
+    }
+}
+
+impl TryFrom<AnySignedMessage> for SignedMessageAPI {
+    type Error = SignerError;
+
+    fn try_from(signed_message: AnySignedMessage) -> Result<SignedMessageAPI, Self::Error> {
+        Ok(SignedMessageAPI {
+            message: UnsignedMessageAPI::try_from(signed_message.message)?,
+            signature: SignatureAPI::from(signed_message.signature),
+        })
+    }
+}
+
+/// Structure containing an `AnyUnsignedMessage` or an `AnySignedMessage`
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum AnyMessageTx {
+    UnsignedMessage(AnyUnsignedMessage),
+    SignedMessage(AnySignedMessage),
+}
+
+impl AnyMessageTx {
+    /// Return the unsigned message
+    pub fn message(&self) -> &AnyUnsignedMessage {
+        match self {
+            AnyMessageTx::UnsignedMessage(message) => message,
+            AnyMessageTx::SignedMessage(signed_message) => &signed_message.message,
+        }
+    }
+
+    pub fn set_network(&mut self, network: Network) -> &mut Self {
+        match self {
+            AnyMessageTx::UnsignedMessage(message) => {
+                message.set_network(network);
+            }
+            AnyMessageTx::SignedMessage(signed_message) => {
+                signed_message.message.set_network(network);
+            }
+        }
+        self
+    }
+}
+
 impl From<&Signature> for SignatureAPI {
     fn from(sig: &Signature) -> SignatureAPI {
         match sig {
@@ -738,6 +983,10 @@ impl From<&Signature> for SignatureAPI {
                 sig_type: SigTypes::SigTypeBLS as u8,
                 data: sig_bls.0.to_vec(),
             },
+            Signature::SignatureDelegated(sig_delegated) => SignatureAPI {
+                sig_type: SigTypes::SigTypeDelegated as u8,
+                data: sig_delegated.0.to_vec(),
+            },
         }
     }
 }
@@ -756,7 +1005,7 @@ impl TryFrom<&SignatureAPI> for signature::Signature {
     }
 }
 
-mod serde_base64_vector {
+pub(crate) mod serde_base64_vector {
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn serialize<S>(v: &[u8], serializer: S) -> Result<S::Ok, S::Error>
@@ -901,13 +1150,75 @@ impl From<SignedMessage> for SignedMessageAPI {
         SignedMessageAPI {
             message: UnsignedMessageAPI::from(signed_message.message().clone()),
             signature: SignatureAPI {
-                sig_type: SigTypes::SigTypeSecp256k1 as u8,
+                sig_type: signed_message.signature().signature_type() as u8,
                 data: signed_message.signature().bytes().to_vec(),
             },
         }
     }
 }
 
+impl TryFrom<&UnsignedMessageAPI> for AnyUnsignedMessage {
+    type Error = SignerError;
+
+    fn try_from(message_api: &UnsignedMessageAPI) -> Result<AnyUnsignedMessage, Self::Error> {
+        let message_params_bytes = base64::decode(&message_api.params)
+            .map_err(|err| SignerError::GenericString(err.to_string()))?;
+
+        Ok(AnyUnsignedMessage {
+            version: 0,
+            to: AnyAddress::from_str(&message_api.to)?,
+            from: AnyAddress::from_str(&message_api.from)?,
+            sequence: message_api.nonce,
+            value: BigInt::from_str(&message_api.value)?,
+            gas_limit: message_api.gas_limit,
+            gas_fee_cap: BigInt::from_str(&message_api.gas_fee_cap)?,
+            gas_premium: BigInt::from_str(&message_api.gas_premium)?,
+            method_num: message_api.method,
+            params: Serialized::new(message_params_bytes),
+        })
+    }
+}
+
+impl TryFrom<AnyUnsignedMessage> for UnsignedMessageAPI {
+    type Error = SignerError;
+
+    fn try_from(message: AnyUnsignedMessage) -> Result<UnsignedMessageAPI, Self::Error> {
+        if message.version != 0 {
+            return Err(SignerError::GenericString(format!(
+                "Unsupported message version {}",
+                message.version
+            )));
+        }
+
+        Ok(UnsignedMessageAPI {
+            to: message.to.to_string(),
+            from: message.from.to_string(),
+            nonce: message.sequence,
+            value: message.value.to_string(),
+            gas_limit: message.gas_limit,
+            gas_fee_cap: message.gas_fee_cap.to_string(),
+            gas_premium: message.gas_premium.to_string(),
+            method: message.method_num,
+            params: base64::encode(message.params.bytes()),
+        })
+    }
+}
+
+impl TryFrom<AnyMessageTx> for MessageTxAPI {
+    type Error = SignerError;
+
+    fn try_from(message_tx: AnyMessageTx) -> Result<MessageTxAPI, Self::Error> {
+        match message_tx {
+            AnyMessageTx::UnsignedMessage(message) => Ok(MessageTxAPI::UnsignedMessageAPI(
+                UnsignedMessageAPI::try_from(message)?,
+            )),
+            AnyMessageTx::SignedMessage(signed_message) => Ok(MessageTxAPI::SignedMessageAPI(
+                SignedMessageAPI::try_from(signed_message)?,
+            )),
+        }
+    }
+}
+
 impl TryFrom<&SignedMessageAPI> for SignedMessage {
     type Error = SignerError;
 
@@ -0,0 +1,235 @@
+use hkdf::Hkdf;
+use num_bigint_chainsafe::BigUint;
+use num_traits::Zero;
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+use std::fmt;
+use zeroize::Zeroize;
+
+use crate::error::SignerError;
+
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+const HKDF_MOD_R_LENGTH: usize = 48;
+const LAMPORT_CHUNKS: usize = 255;
+const CHUNK_SIZE: usize = 32;
+const MIN_SEED_SIZE: usize = 32;
+
+/// BLS12-381 curve order `r` (big endian)
+const CURVE_ORDER: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+pub const BLS_SECRET_KEY_SIZE: usize = 32;
+
+/// BLS secret key derived following EIP-2333 (stored as a big endian scalar)
+#[derive(Zeroize)]
+#[zeroize(drop)]
+pub struct BLSSecretKey([u8; BLS_SECRET_KEY_SIZE]);
+
+impl fmt::Display for BLSSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretKey:  {:?}", hex::encode(&self.0))
+    }
+}
+
+impl TryFrom<&[u8]> for BLSSecretKey {
+    type Error = SignerError;
+
+    /// Derive the master secret key from a seed (`derive_master_SK`)
+    fn try_from(seed: &[u8]) -> Result<BLSSecretKey, Self::Error> {
+        if seed.len() < MIN_SEED_SIZE {
+            return Err(SignerError::GenericString(
+                "Seed should be at least 32 bytes".to_string(),
+            ));
+        }
+
+        hkdf_mod_r(seed)
+    }
+}
+
+impl BLSSecretKey {
+    fn from_scalar(scalar: &BigUint) -> Self {
+        let bytes = scalar.to_bytes_be();
+
+        let mut sk = BLSSecretKey {
+            0: [0; BLS_SECRET_KEY_SIZE],
+        };
+        sk.0[BLS_SECRET_KEY_SIZE - bytes.len()..].copy_from_slice(&bytes);
+        sk
+    }
+
+    /// Secret key bytes in the little endian format used by `bls_signatures` and Lotus
+    #[inline]
+    pub fn secret_key(&self) -> [u8; BLS_SECRET_KEY_SIZE] {
+        let mut sk = self.0;
+        sk.reverse();
+        sk
+    }
+
+    pub fn derive_child_key(&self, child_index: u32) -> Result<BLSSecretKey, SignerError> {
+        let compressed_lamport_pk = parent_sk_to_lamport_pk(&self.0, child_index)?;
+
+        hkdf_mod_r(&compressed_lamport_pk)
+    }
+
+    pub fn derive_path(&self, path: &BLSPath) -> Result<BLSSecretKey, SignerError> {
+        let (first, rest) = path
+            .0
+            .split_first()
+            .ok_or_else(|| SignerError::GenericString("Empty BLS path".to_string()))?;
+
+        let mut child = self.derive_child_key(*first)?;
+        for index in rest {
+            child = child.derive_child_key(*index)?;
+        }
+
+        Ok(child)
+    }
+}
+
+/// Derivation path following EIP-2334 (e.g `m/12381/461/0/0`)
+pub struct BLSPath(pub Vec<u32>);
+
+impl BLSPath {
+    pub fn from_string(path: &str) -> Result<Self, SignerError> {
+        let mut nodes = path.split('/');
+
+        if nodes.next() != Some("m") {
+            return Err(SignerError::GenericString(
+                "Invalid BLS path: should start with `m`".to_string(),
+            ));
+        }
+
+        let indexes = nodes
+            .map(|node| node.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()?;
+
+        if indexes.is_empty() {
+            return Err(SignerError::GenericString(
+                "Invalid BLS path: should contain at least one index".to_string(),
+            ));
+        }
+
+        Ok(BLSPath(indexes))
+    }
+
+    /// The coin type `1` is used for testnet
+    pub fn is_testnet(&self) -> bool {
+        self.0.get(1) == Some(&1)
+    }
+}
+
+fn hkdf_mod_r(ikm: &[u8]) -> Result<BLSSecretKey, SignerError> {
+    let curve_order = BigUint::from_bytes_be(&CURVE_ORDER);
+
+    let mut ikm_postfixed = ikm.to_vec();
+    ikm_postfixed.push(0);
+
+    let info = (HKDF_MOD_R_LENGTH as u16).to_be_bytes();
+
+    let mut salt = KEYGEN_SALT.to_vec();
+    loop {
+        salt = Sha256::digest(&salt).to_vec();
+
+        let mut okm = [0u8; HKDF_MOD_R_LENGTH];
+        Hkdf::<Sha256>::new(Some(salt.as_slice()), &ikm_postfixed).expand(&info, &mut okm)?;
+
+        let scalar = BigUint::from_bytes_be(&okm) % &curve_order;
+        okm.zeroize();
+
+        if !scalar.is_zero() {
+            return Ok(BLSSecretKey::from_scalar(&scalar));
+        }
+    }
+}
+
+fn ikm_to_lamport_sk(ikm: &[u8], salt: &[u8]) -> Result<Vec<u8>, SignerError> {
+    let mut okm = vec![0u8; LAMPORT_CHUNKS * CHUNK_SIZE];
+    Hkdf::<Sha256>::new(Some(salt), ikm).expand(&[], &mut okm)?;
+
+    Ok(okm)
+}
+
+fn parent_sk_to_lamport_pk(
+    parent_sk: &[u8; BLS_SECRET_KEY_SIZE],
+    index: u32,
+) -> Result<[u8; 32], SignerError> {
+    let salt = index.to_be_bytes();
+    let mut not_ikm = *parent_sk;
+    not_ikm.iter_mut().for_each(|byte| *byte = !*byte);
+
+    let lamport_0 = ikm_to_lamport_sk(parent_sk, &salt)?;
+    let lamport_1 = ikm_to_lamport_sk(&not_ikm, &salt)?;
+    not_ikm.zeroize();
+
+    let mut hasher = Sha256::new();
+    for chunk in lamport_0
+        .chunks(CHUNK_SIZE)
+        .chain(lamport_1.chunks(CHUNK_SIZE))
+    {
+        hasher.update(Sha256::digest(chunk));
+    }
+
+    let mut compressed_lamport_pk = [0u8; 32];
+    compressed_lamport_pk.copy_from_slice(&hasher.finalize());
+
+    Ok(compressed_lamport_pk)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bls_key::{BLSPath, BLSSecretKey};
+    use hex::{decode, encode};
+    use std::convert::TryFrom;
+
+    // EIP-2333 test case 0
+    const SEED: &str = "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04";
+
+    #[test]
+    fn derive_master_key() {
+        let master = BLSSecretKey::try_from(decode(SEED).unwrap().as_slice()).unwrap();
+
+        // 6083874454709270928345386274498605044986640685124978867557563392430687146096
+        assert_eq!(
+            encode(master.0),
+            "0d7359d57963ab8fbbde1852dcf553fedbc31f464d80ee7d40ae683122b45070"
+        );
+    }
+
+    #[test]
+    fn derive_child() {
+        let master = BLSSecretKey::try_from(decode(SEED).unwrap().as_slice()).unwrap();
+        let child = master.derive_child_key(0).unwrap();
+
+        // 20397789859736650942317412262472558107875392172444076792671091975210932703118
+        assert_eq!(
+            encode(child.0),
+            "2d18bd6c14e6d15bf8b5085c9b74f3daae3b03cc2014770a599d8c1539e50f8e"
+        );
+
+        // bls_signatures expects little endian
+        assert_eq!(
+            encode(child.secret_key()),
+            "8e0fe539158c9d590a771420cc033baedaf3749b5c08b5f85bd1e6146cbd182d"
+        );
+    }
+
+    #[test]
+    fn short_seed_is_rejected() {
+        assert!(BLSSecretKey::try_from(&[0u8; 16][..]).is_err());
+    }
+
+    #[test]
+    fn create_derive_path() {
+        let path = BLSPath::from_string("m/12381/461/0/0").unwrap();
+
+        assert_eq!(path.0, vec![12381, 461, 0, 0]);
+        assert!(!path.is_testnet());
+
+        assert!(BLSPath::from_string("m/12381/1/0/0").unwrap().is_testnet());
+        assert!(BLSPath::from_string("m/44'/461'/0/0/0").is_err());
+        assert!(BLSPath::from_string("12381/461").is_err());
+        assert!(BLSPath::from_string("m").is_err());
+    }
+}
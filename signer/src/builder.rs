@@ -0,0 +1,441 @@
+use forest_address::Address;
+use forest_cid::{multihash::MultihashDigest, Cid, Code::Identity};
+use forest_encoding::blake2b_256;
+use forest_vm::Serialized;
+
+use extras::{multisig, paych, ExecParams, MethodInit, INIT_ACTOR_ADDR};
+
+use crate::address::AnyAddress;
+use crate::api::UnsignedMessageAPI;
+use crate::error::SignerError;
+use crate::token::TokenAmount;
+use crate::validation::validate_message;
+
+/// Actor method call plugged into a `MessageBuilder`: the method number, the serialized params
+/// and, for calls to a singleton actor (e.g the init actor), the receiver of the message.
+pub trait MessageParamsProvider {
+    fn method(&self) -> u64;
+
+    fn params(&self) -> Result<Serialized, SignerError>;
+
+    /// Receiver of the message if the call always goes to the same actor
+    fn to(&self) -> Option<Address> {
+        None
+    }
+}
+
+fn serialize<T: serde::Serialize>(params: T) -> Result<Serialized, SignerError> {
+    Serialized::serialize(params).map_err(|err| SignerError::GenericString(err.to_string()))
+}
+
+fn init_exec(code: &[u8], constructor_params: Serialized) -> Result<Serialized, SignerError> {
+    serialize(ExecParams {
+        code_cid: Cid::new_v1(forest_cid::RAW, Identity.digest(code)),
+        constructor_params,
+    })
+}
+
+/// Create a multisig wallet (init actor `Exec`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateMultisig {
+    pub signers: Vec<Address>,
+    pub num_approvals_threshold: i64,
+    /// Unlock duration in epochs, `0` or `-1` if the funds aren't locked
+    pub unlock_duration: i64,
+    pub start_epoch: i64,
+}
+
+impl MessageParamsProvider for CreateMultisig {
+    fn method(&self) -> u64 {
+        MethodInit::Exec as u64
+    }
+
+    fn params(&self) -> Result<Serialized, SignerError> {
+        if self.unlock_duration < -1 {
+            return Err(SignerError::GenericString(
+                "Invalid duration value (duration >= -1)".to_string(),
+            ));
+        }
+
+        let constructor_params = serialize(multisig::ConstructorParams {
+            signers: self.signers.clone(),
+            num_approvals_threshold: self.num_approvals_threshold,
+            unlock_duration: self.unlock_duration,
+            start_epoch: self.start_epoch,
+        })?;
+
+        init_exec(b"fil/5/multisig", constructor_params)
+    }
+
+    fn to(&self) -> Option<Address> {
+        Some(*INIT_ACTOR_ADDR)
+    }
+}
+
+/// Propose a transaction to a multisig wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposeMultisig {
+    pub to: AnyAddress,
+    pub value: TokenAmount,
+    pub method: u64,
+    /// Serialized params of the proposed transaction
+    pub params: Vec<u8>,
+}
+
+impl MessageParamsProvider for ProposeMultisig {
+    fn method(&self) -> u64 {
+        multisig::MethodMultisig::Propose as u64
+    }
+
+    fn params(&self) -> Result<Serialized, SignerError> {
+        serialize(multisig::ProposeParams {
+            to: self.to.clone(),
+            value: self.value.atto().clone(),
+            method: self.method,
+            params: Serialized::new(self.params.clone()),
+        })
+    }
+}
+
+/// Pending multisig transaction (a value transfer), identified by its id and proposal hash
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultisigTransaction {
+    pub txn_id: i64,
+    pub requester: Address,
+    pub to: AnyAddress,
+    pub value: TokenAmount,
+}
+
+impl MultisigTransaction {
+    fn params(&self) -> Result<Serialized, SignerError> {
+        let proposal_hash_data = serialize(multisig::ProposalHashData {
+            requester: self.requester,
+            to: self.to.clone(),
+            value: self.value.atto().clone(),
+            method: 0,
+            params: Serialized::new(Vec::new()),
+        })?;
+
+        serialize(multisig::TxnIDParams {
+            id: multisig::TxnID(self.txn_id),
+            proposal_hash: blake2b_256(&proposal_hash_data).to_vec(),
+        })
+    }
+}
+
+/// Approve a pending multisig transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApproveMultisig(pub MultisigTransaction);
+
+impl MessageParamsProvider for ApproveMultisig {
+    fn method(&self) -> u64 {
+        multisig::MethodMultisig::Approve as u64
+    }
+
+    fn params(&self) -> Result<Serialized, SignerError> {
+        self.0.params()
+    }
+}
+
+/// Cancel a pending multisig transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct CancelMultisig(pub MultisigTransaction);
+
+impl MessageParamsProvider for CancelMultisig {
+    fn method(&self) -> u64 {
+        multisig::MethodMultisig::Cancel as u64
+    }
+
+    fn params(&self) -> Result<Serialized, SignerError> {
+        self.0.params()
+    }
+}
+
+/// Create a payment channel (init actor `Exec`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatePaymentChannel {
+    pub from: Address,
+    pub to: AnyAddress,
+}
+
+impl MessageParamsProvider for CreatePaymentChannel {
+    fn method(&self) -> u64 {
+        MethodInit::Exec as u64
+    }
+
+    fn params(&self) -> Result<Serialized, SignerError> {
+        let constructor_params = serialize(paych::ConstructorParams {
+            from: self.from,
+            to: self.to.clone(),
+        })?;
+
+        init_exec(b"fil/5/paymentchannel", constructor_params)
+    }
+
+    fn to(&self) -> Option<Address> {
+        Some(*INIT_ACTOR_ADDR)
+    }
+}
+
+/// Update a payment channel state with a signed voucher
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdatePaymentChannel {
+    /// CBOR encoded signed voucher
+    pub signed_voucher: Vec<u8>,
+}
+
+impl MessageParamsProvider for UpdatePaymentChannel {
+    fn method(&self) -> u64 {
+        paych::MethodsPaych::UpdateChannelState as u64
+    }
+
+    fn params(&self) -> Result<Serialized, SignerError> {
+        let sv: paych::SignedVoucher = forest_encoding::from_slice(&self.signed_voucher)?;
+
+        serialize(paych::UpdateChannelStateParams { sv, secret: vec![] })
+    }
+}
+
+/// Settle a payment channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SettlePaymentChannel;
+
+impl MessageParamsProvider for SettlePaymentChannel {
+    fn method(&self) -> u64 {
+        paych::MethodsPaych::Settle as u64
+    }
+
+    fn params(&self) -> Result<Serialized, SignerError> {
+        Ok(Serialized::new(Vec::new()))
+    }
+}
+
+/// Collect the funds of a settled payment channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollectPaymentChannel;
+
+impl MessageParamsProvider for CollectPaymentChannel {
+    fn method(&self) -> u64 {
+        paych::MethodsPaych::Collect as u64
+    }
+
+    fn params(&self) -> Result<Serialized, SignerError> {
+        Ok(Serialized::new(Vec::new()))
+    }
+}
+
+/// Fluent builder of `UnsignedMessageAPI`. The message is a plain transfer (method 0) unless a
+/// method call is set with `call`. `build` checks the message with `validate_message` and fails
+/// on the first error found while building.
+///
+/// ```ignore
+/// let message = MessageBuilder::new(from)
+///     .nonce(1)
+///     .value(TokenAmount::from_str("1 FIL")?)
+///     .gas_limit(1000000)
+///     .gas_fee_cap(TokenAmount::from(2500))
+///     .gas_premium(TokenAmount::from(2500))
+///     .call(&CreateMultisig { signers, num_approvals_threshold: 2, unlock_duration: 0, start_epoch: 0 })
+///     .build()?;
+/// ```
+#[derive(Debug)]
+pub struct MessageBuilder {
+    from: AnyAddress,
+    to: Option<AnyAddress>,
+    nonce: u64,
+    value: TokenAmount,
+    gas_limit: i64,
+    gas_fee_cap: TokenAmount,
+    gas_premium: TokenAmount,
+    method: u64,
+    params: Serialized,
+    error: Option<SignerError>,
+}
+
+impl MessageBuilder {
+    /// Start a message sent by `from`
+    pub fn new<A: Into<AnyAddress>>(from: A) -> Self {
+        MessageBuilder {
+            from: from.into(),
+            to: None,
+            nonce: 0,
+            value: TokenAmount::default(),
+            gas_limit: 0,
+            gas_fee_cap: TokenAmount::default(),
+            gas_premium: TokenAmount::default(),
+            method: 0,
+            params: Serialized::new(Vec::new()),
+            error: None,
+        }
+    }
+
+    pub fn to<A: Into<AnyAddress>>(mut self, to: A) -> Self {
+        self.to = Some(to.into());
+        self
+    }
+
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    pub fn value(mut self, value: TokenAmount) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn gas_limit(mut self, gas_limit: i64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    pub fn gas_fee_cap(mut self, gas_fee_cap: TokenAmount) -> Self {
+        self.gas_fee_cap = gas_fee_cap;
+        self
+    }
+
+    pub fn gas_premium(mut self, gas_premium: TokenAmount) -> Self {
+        self.gas_premium = gas_premium;
+        self
+    }
+
+    /// Set a raw method call (method number and serialized params)
+    pub fn method(mut self, method: u64, params: Serialized) -> Self {
+        self.method = method;
+        self.params = params;
+        self
+    }
+
+    /// Set an actor method call. Calls to a singleton actor also set the receiver, on the network
+    /// of the sender.
+    pub fn call<P: MessageParamsProvider + ?Sized>(mut self, call: &P) -> Self {
+        self.method = call.method();
+
+        match call.params() {
+            Ok(params) => self.params = params,
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+
+        if let Some(mut to) = call.to() {
+            to.set_network(self.from.network());
+            self.to = Some(to.into());
+        }
+
+        self
+    }
+
+    /// Build and validate the message
+    pub fn build(self) -> Result<UnsignedMessageAPI, SignerError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let to = self
+            .to
+            .ok_or_else(|| SignerError::GenericString("Missing `to` address".to_string()))?;
+
+        let message = UnsignedMessageAPI {
+            to: to.to_string(),
+            from: self.from.to_string(),
+            nonce: self.nonce,
+            value: self.value.to_atto_string(),
+            gas_limit: self.gas_limit,
+            gas_fee_cap: self.gas_fee_cap.to_atto_string(),
+            gas_premium: self.gas_premium.to_atto_string(),
+            method: self.method,
+            params: base64::encode(self.params.bytes()),
+        };
+
+        let report = validate_message(&message);
+        if !report.is_valid() {
+            return Err(SignerError::InvalidMessage(report));
+        }
+
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const FROM: &str = "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba";
+    const TO: &str = "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy";
+
+    fn builder() -> MessageBuilder {
+        MessageBuilder::new(Address::from_str(FROM).unwrap())
+            .nonce(1)
+            .gas_limit(25000)
+            .gas_fee_cap(TokenAmount::from(2500))
+            .gas_premium(TokenAmount::from(2500))
+    }
+
+    #[test]
+    fn build_transfer() {
+        let message = builder()
+            .to(Address::from_str(TO).unwrap())
+            .value(TokenAmount::from_str("1 FIL").unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(message.to, TO);
+        assert_eq!(message.from, FROM);
+        assert_eq!(message.value, "1000000000000000000");
+        assert_eq!(message.method, 0);
+        assert_eq!(message.params, "");
+    }
+
+    #[test]
+    fn build_actor_call() {
+        let message = builder()
+            .call(&CreateMultisig {
+                signers: vec![Address::from_str(FROM).unwrap()],
+                num_approvals_threshold: 1,
+                unlock_duration: 0,
+                start_epoch: 0,
+            })
+            .build()
+            .unwrap();
+
+        // The init actor on the network of the sender
+        assert_eq!(message.to, "t01");
+        assert_eq!(message.method, MethodInit::Exec as u64);
+
+        let message = MessageBuilder::new(Address::from_str("f01234").unwrap())
+            .to(Address::from_str("f01").unwrap())
+            .call(&SettlePaymentChannel);
+        assert!(message.build().is_err());
+    }
+
+    #[test]
+    fn build_errors() {
+        // Missing receiver
+        assert!(builder().build().is_err());
+
+        // Invalid params
+        assert!(builder()
+            .call(&CreateMultisig {
+                signers: vec![],
+                num_approvals_threshold: 1,
+                unlock_duration: -2,
+                start_epoch: 0,
+            })
+            .build()
+            .is_err());
+
+        // Validation error
+        match builder()
+            .to(Address::from_str(TO).unwrap())
+            .gas_fee_cap(TokenAmount::from(100))
+            .build()
+        {
+            Err(SignerError::InvalidMessage(report)) => {
+                assert_eq!(report.errors[0].field, "gas_fee_cap")
+            }
+            _ => panic!("Should be an invalid message"),
+        }
+    }
+}
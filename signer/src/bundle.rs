@@ -0,0 +1,332 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use forest_address::Network;
+use serde::{Deserialize, Serialize};
+
+use crate::address::AnyAddress;
+use crate::api::{
+    AnyUnsignedMessage, MessageParams, SignatureAPI, SignedMessageAPI, UnsignedMessageAPI,
+};
+use crate::error::SignerError;
+use crate::signer::Signer;
+use crate::token::TokenAmount;
+use crate::validation::{validate_message, ValidationIssue};
+use crate::{
+    deserialize_params, key_recover, key_recover_bls, transaction_sign_with_signer,
+    verify_signed_message, PrivateKey,
+};
+
+/// Version of the bundle format
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Message to add to a bundle
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BundleMessage {
+    pub message: UnsignedMessageAPI,
+    /// Actor code of the receiver (e.g `fil/5/multisig`), used to decode the params
+    #[serde(default)]
+    pub actor_type: Option<String>,
+}
+
+/// Message of a bundle, with its CID, decoded params and signature (once signed)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BundleEntry {
+    pub message: UnsignedMessageAPI,
+    pub cid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded_params: Option<MessageParams>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<SignatureAPI>,
+}
+
+/// Unsigned transactions bundle carried to an offline signer. It holds the expected network, an
+/// optional fee ceiling and the messages with their CIDs and decoded params. Signatures are added
+/// as the messages are signed, the bundle can be signed by several keys.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SigningBundle {
+    pub version: u32,
+    pub testnet: bool,
+    /// Maximum fee (`gas_limit * gas_fee_cap`) of each message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<TokenAmount>,
+    pub entries: Vec<BundleEntry>,
+}
+
+/// Human readable description of a bundle message
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BundleEntrySummary {
+    pub cid: String,
+    pub from: String,
+    pub to: String,
+    pub nonce: u64,
+    /// Value in FIL (e.g `1.5 FIL`)
+    pub value: String,
+    /// Maximum fee in FIL
+    pub max_fee: String,
+    pub method: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<MessageParams>,
+    pub signed: bool,
+    pub warnings: Vec<ValidationIssue>,
+}
+
+fn network(testnet: bool) -> Network {
+    if testnet {
+        Network::Testnet
+    } else {
+        Network::Mainnet
+    }
+}
+
+fn message_max_fee(message: &UnsignedMessageAPI) -> Result<TokenAmount, SignerError> {
+    let gas_limit = u64::try_from(message.gas_limit)
+        .map_err(|_| SignerError::GenericString("Negative gas limit".to_string()))?;
+
+    TokenAmount::from_str(&message.gas_fee_cap)?
+        .checked_mul(gas_limit)
+        .ok_or_else(|| SignerError::GenericString("Fee overflow".to_string()))
+}
+
+fn decode_params(
+    message: &UnsignedMessageAPI,
+    actor_type: &Option<String>,
+) -> Result<Option<MessageParams>, SignerError> {
+    match actor_type {
+        Some(actor_type) if !message.params.is_empty() => Ok(Some(deserialize_params(
+            message.params.clone(),
+            actor_type.clone(),
+            message.method,
+        )?)),
+        _ => Ok(None),
+    }
+}
+
+/// Check a message against the bundle rules and return the validation warnings
+fn check_message(
+    bundle: &SigningBundle,
+    message: &UnsignedMessageAPI,
+) -> Result<Vec<ValidationIssue>, SignerError> {
+    let report = validate_message(message);
+    if !report.is_valid() {
+        return Err(SignerError::InvalidMessage(report));
+    }
+
+    if AnyAddress::from_str(&message.from)?.network() != network(bundle.testnet) {
+        return Err(SignerError::GenericString(format!(
+            "Message from {} is not on the bundle network",
+            message.from
+        )));
+    }
+
+    if let Some(max_fee) = &bundle.max_fee {
+        let fee = message_max_fee(message)?;
+        if fee > *max_fee {
+            return Err(SignerError::GenericString(format!(
+                "Message fee {} is above the bundle maximum fee {}",
+                fee, max_fee
+            )));
+        }
+    }
+
+    Ok(report.warnings)
+}
+
+/// Check the version, the entries and the signatures of a bundle
+fn check_bundle(bundle: &SigningBundle) -> Result<Vec<BundleEntrySummary>, SignerError> {
+    if bundle.version != BUNDLE_VERSION {
+        return Err(SignerError::GenericString(format!(
+            "Unsupported bundle version {}",
+            bundle.version
+        )));
+    }
+
+    bundle
+        .entries
+        .iter()
+        .map(|entry| {
+            let warnings = check_message(bundle, &entry.message)?;
+
+            let cid = AnyUnsignedMessage::try_from(&entry.message)?.cid()?;
+            if entry.cid != cid.to_string() {
+                return Err(SignerError::GenericString(format!(
+                    "CID mismatch: expected {} but got {}",
+                    cid, entry.cid
+                )));
+            }
+
+            // Never trust the decoded params of the bundle, decode them again
+            let params = decode_params(&entry.message, &entry.actor_type)?;
+            if serde_json::to_value(&params)? != serde_json::to_value(&entry.decoded_params)? {
+                return Err(SignerError::GenericString(format!(
+                    "Decoded params don't match the params of message {}",
+                    entry.cid
+                )));
+            }
+
+            if let Some(signature) = &entry.signature {
+                let signed_message = SignedMessageAPI {
+                    message: entry.message.clone(),
+                    signature: signature.clone(),
+                };
+                if !verify_signed_message(&signed_message)? {
+                    return Err(SignerError::GenericString(format!(
+                        "Invalid signature for message {}",
+                        entry.cid
+                    )));
+                }
+            }
+
+            Ok(BundleEntrySummary {
+                cid: entry.cid.clone(),
+                from: entry.message.from.clone(),
+                to: entry.message.to.clone(),
+                nonce: entry.message.nonce,
+                value: TokenAmount::from_str(&entry.message.value)?.to_string(),
+                max_fee: message_max_fee(&entry.message)?.to_string(),
+                method: entry.message.method,
+                params,
+                signed: entry.signature.is_some(),
+                warnings,
+            })
+        })
+        .collect()
+}
+
+/// Create a signing bundle. Every message is validated, must be on the bundle network and, if
+/// `max_fee` is set, must not cost more than `max_fee`.
+///
+/// # Arguments
+///
+/// * `messages` - the messages, with the actor type of their receiver to decode the params
+/// * `testnet` - the expected network, `true` if testnet else `false` for mainnet
+/// * `max_fee` - optional maximum fee (`gas_limit * gas_fee_cap`) of each message
+///
+pub fn bundle_create(
+    messages: Vec<BundleMessage>,
+    testnet: bool,
+    max_fee: Option<TokenAmount>,
+) -> Result<SigningBundle, SignerError> {
+    let mut bundle = SigningBundle {
+        version: BUNDLE_VERSION,
+        testnet,
+        max_fee,
+        entries: Vec::with_capacity(messages.len()),
+    };
+
+    for BundleMessage {
+        message,
+        actor_type,
+    } in messages
+    {
+        check_message(&bundle, &message)?;
+
+        bundle.entries.push(BundleEntry {
+            cid: AnyUnsignedMessage::try_from(&message)?.cid()?.to_string(),
+            decoded_params: decode_params(&message, &actor_type)?,
+            actor_type,
+            message,
+            signature: None,
+        });
+    }
+
+    Ok(bundle)
+}
+
+/// Verify a bundle (version, network, fees, CIDs, decoded params and signatures) and describe its
+/// messages.
+///
+/// # Arguments
+///
+/// * `bundle` - the signing bundle
+///
+pub fn bundle_inspect(bundle: &SigningBundle) -> Result<Vec<BundleEntrySummary>, SignerError> {
+    check_bundle(bundle)
+}
+
+/// Sign the unsigned messages of the bundle sent from the signer address. Return the number of
+/// messages signed.
+///
+/// # Arguments
+///
+/// * `bundle` - the signing bundle
+/// * `signer` - a `Signer`
+///
+pub fn bundle_sign_with_signer<S: Signer + ?Sized>(
+    bundle: &mut SigningBundle,
+    signer: &S,
+) -> Result<usize, SignerError> {
+    check_bundle(bundle)?;
+
+    let mut signer_address = AnyAddress::from_str(signer.address())?;
+    signer_address.set_network(network(bundle.testnet));
+
+    let mut signed = 0;
+    for entry in bundle.entries.iter_mut() {
+        if entry.signature.is_none() && AnyAddress::from_str(&entry.message.from)? == signer_address
+        {
+            let signed_message = transaction_sign_with_signer(&entry.message, signer)?;
+            entry.signature = Some(signed_message.signature);
+            signed += 1;
+        }
+    }
+
+    Ok(signed)
+}
+
+/// Sign the unsigned messages of the bundle sent from the address of a private key (secp256k1 or
+/// BLS). Return the number of messages signed.
+///
+/// # Arguments
+///
+/// * `bundle` - the signing bundle
+/// * `private_key` - a `PrivateKey`
+///
+pub fn bundle_sign(
+    bundle: &mut SigningBundle,
+    private_key: &PrivateKey,
+) -> Result<usize, SignerError> {
+    let mut signed = 0;
+
+    if let Ok(key) = key_recover(private_key, bundle.testnet) {
+        signed += bundle_sign_with_signer(bundle, &key)?;
+    }
+    if let Ok(key) = key_recover_bls(private_key, bundle.testnet) {
+        signed += bundle_sign_with_signer(bundle, &key)?;
+    }
+
+    if signed == 0 {
+        return Err(SignerError::GenericString(
+            "No unsigned message of the bundle is sent from this key".to_string(),
+        ));
+    }
+
+    Ok(signed)
+}
+
+/// Verify a fully signed bundle and return its signed messages.
+///
+/// # Arguments
+///
+/// * `bundle` - the signing bundle
+///
+pub fn bundle_finalize(bundle: &SigningBundle) -> Result<Vec<SignedMessageAPI>, SignerError> {
+    check_bundle(bundle)?;
+
+    bundle
+        .entries
+        .iter()
+        .map(|entry| match &entry.signature {
+            Some(signature) => Ok(SignedMessageAPI {
+                message: entry.message.clone(),
+                signature: signature.clone(),
+            }),
+            None => Err(SignerError::GenericString(format!(
+                "Message {} is not signed",
+                entry.cid
+            ))),
+        })
+        .collect()
+}
@@ -44,6 +44,30 @@ pub enum SignerError {
     // forest encoding error
     #[error("Encoding error | {0}")]
     EncodingError(#[from] forest_encoding::error::Error),
+    /// Mnemonic checksum doesn't match
+    #[error("Invalid mnemonic checksum")]
+    InvalidMnemonicChecksum,
+    /// Mnemonic contains a word that is not part of the wordlist
+    #[error("Unknown mnemonic word `{0}`")]
+    UnknownMnemonicWord(String),
+    /// Mnemonic word count is not 12, 15, 18, 21 or 24
+    #[error("Invalid mnemonic word count: {0}")]
+    InvalidMnemonicWordCount(usize),
+    /// Entropy length doesn't match the mnemonic word count
+    #[error("Invalid entropy length: {0} bits")]
+    InvalidEntropyLength(usize),
+    /// JSON error
+    #[error("JSON error | {0}")]
+    JSON(#[from] serde_json::Error),
+    /// IO error
+    #[error("IO error | {0}")]
+    IO(#[from] std::io::Error),
+    /// Keystore entry couldn't be decrypted
+    #[error("Invalid password")]
+    InvalidPassword,
+    /// Message refused by the strict mode validation
+    #[error("Invalid message | {0}")]
+    InvalidMessage(crate::validation::ValidationReport),
 }
 
 #[cfg(feature = "with-ffi-support")]
@@ -63,6 +87,14 @@ impl From<SignerError> for ffi_support::ExternError {
             SignerError::DecodeError(_) => 11,
             SignerError::DeserializeError(_) => 12,
             SignerError::EncodingError(_) => 13,
+            SignerError::InvalidMnemonicChecksum => 14,
+            SignerError::UnknownMnemonicWord(_) => 15,
+            SignerError::InvalidMnemonicWordCount(_) => 16,
+            SignerError::InvalidEntropyLength(_) => 17,
+            SignerError::JSON(_) => 18,
+            SignerError::IO(_) => 19,
+            SignerError::InvalidPassword => 20,
+            SignerError::InvalidMessage(_) => 21,
         };
         Self::new_error(ffi_support::ErrorCode::new(code), e.to_string())
     }
@@ -75,6 +107,18 @@ impl From<InvalidKeyLength> for SignerError {
     }
 }
 
+impl From<getrandom::Error> for SignerError {
+    fn from(err: getrandom::Error) -> SignerError {
+        SignerError::GenericString(err.to_string())
+    }
+}
+
+impl From<hkdf::InvalidLength> for SignerError {
+    fn from(err: hkdf::InvalidLength) -> SignerError {
+        SignerError::GenericString(err.to_string())
+    }
+}
+
 impl From<forest_address::Error> for SignerError {
     fn from(err: forest_address::Error) -> SignerError {
         SignerError::GenericString(err.to_string())
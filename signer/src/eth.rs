@@ -0,0 +1,392 @@
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use forest_address::Address;
+use forest_encoding::{from_slice, to_vec};
+use num_bigint_chainsafe::{BigInt, Sign};
+use rlp::RlpStream;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::address::{
+    eth_address_from_public_key, f410_address_from_public_key, AnyAddress, DelegatedAddress,
+    EAM_NAMESPACE, ETH_ADDRESS_SIZE,
+};
+use crate::api::{SigTypes, SignatureAPI, SignedMessageAPI, UnsignedMessageAPI};
+use crate::error::SignerError;
+use crate::signature::{Signature, SignatureSECP256K1};
+use crate::signer::{KeyType, Signer};
+use crate::{key_recover, recover_secp256k1_public_key, PrivateKey};
+
+/// EIP-2718 transaction type of EIP-1559 transactions
+pub const EIP1559_TX_TYPE: u8 = 0x02;
+
+/// EIP-155 chain id of Filecoin mainnet
+pub const MAINNET_CHAIN_ID: u64 = 314;
+
+/// EIP-155 chain id of the Filecoin calibration network
+pub const CALIBNET_CHAIN_ID: u64 = 314_159;
+
+/// EVM actor `InvokeContract` method number (FRC-42)
+pub const METHOD_INVOKE_CONTRACT: u64 = 3_844_450_837;
+
+/// Ethereum Address Manager actor `CreateExternal` method number
+pub const METHOD_CREATE_EXTERNAL: u64 = 4;
+
+/// EIP-1559 Ethereum transaction sent from a `f410` account
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EthTransactionAPI {
+    #[serde(alias = "chainId")]
+    pub chain_id: u64,
+    pub nonce: u64,
+    /// `0x` Ethereum address of the recipient, `None` to deploy a contract
+    pub to: Option<String>,
+    /// Amount in attoFIL
+    pub value: String,
+    #[serde(alias = "maxFeePerGas")]
+    pub max_fee_per_gas: String,
+    #[serde(alias = "maxPriorityFeePerGas")]
+    pub max_priority_fee_per_gas: String,
+    #[serde(alias = "gasLimit")]
+    pub gas_limit: u64,
+    /// `0x` hex encoded call data
+    #[serde(default)]
+    pub input: String,
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, SignerError> {
+    let value = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+
+    Ok(hex::decode(value)?)
+}
+
+/// Big endian bytes without leading zeros (RLP integer encoding)
+fn trim_leading_zeros(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().copied().skip_while(|b| *b == 0).collect()
+}
+
+fn amount_bytes(amount: &str) -> Result<Vec<u8>, SignerError> {
+    let (sign, bytes) = BigInt::from_str(amount)?.to_bytes_be();
+
+    if sign == Sign::Minus {
+        return Err(SignerError::GenericString(format!(
+            "Negative amount `{}`",
+            amount
+        )));
+    }
+
+    Ok(trim_leading_zeros(&bytes))
+}
+
+fn to_bytes(to: &Option<String>) -> Result<Vec<u8>, SignerError> {
+    match to {
+        Some(to) => {
+            let to = decode_hex(to)?;
+            if to.len() != ETH_ADDRESS_SIZE {
+                return Err(SignerError::GenericString(format!(
+                    "Invalid Ethereum address length {} (should be {} bytes)",
+                    to.len(),
+                    ETH_ADDRESS_SIZE
+                )));
+            }
+            Ok(to)
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+fn rlp_stream(
+    transaction: &EthTransactionAPI,
+    signature: Option<&SignatureSECP256K1>,
+) -> Result<Vec<u8>, SignerError> {
+    let mut stream = RlpStream::new_list(if signature.is_some() { 12 } else { 9 });
+
+    stream.append(&transaction.chain_id);
+    stream.append(&transaction.nonce);
+    stream.append(&amount_bytes(&transaction.max_priority_fee_per_gas)?);
+    stream.append(&amount_bytes(&transaction.max_fee_per_gas)?);
+    stream.append(&transaction.gas_limit);
+    stream.append(&to_bytes(&transaction.to)?);
+    stream.append(&amount_bytes(&transaction.value)?);
+    stream.append(&decode_hex(&transaction.input)?);
+    // Empty access list
+    stream.begin_list(0);
+
+    if let Some(signature) = signature {
+        stream.append(&u64::from(signature.0[64]));
+        stream.append(&trim_leading_zeros(&signature.0[..32]));
+        stream.append(&trim_leading_zeros(&signature.0[32..64]));
+    }
+
+    let mut encoded = vec![EIP1559_TX_TYPE];
+    encoded.extend_from_slice(&stream.out());
+
+    Ok(encoded)
+}
+
+/// RLP encode an unsigned EIP-1559 transaction (`0x02 || rlp([chain_id, ..., access_list])`).
+///
+/// # Arguments
+///
+/// * `transaction` - an EIP-1559 transaction
+///
+pub fn eth_transaction_serialize(transaction: &EthTransactionAPI) -> Result<Vec<u8>, SignerError> {
+    rlp_stream(transaction, None)
+}
+
+/// RLP encode a signed EIP-1559 transaction, ready for `eth_sendRawTransaction`.
+///
+/// # Arguments
+///
+/// * `transaction` - an EIP-1559 transaction
+/// * `signature` - the delegated (RSV) signature of the transaction
+///
+pub fn eth_transaction_serialize_signed(
+    transaction: &EthTransactionAPI,
+    signature: &SignatureSECP256K1,
+) -> Result<Vec<u8>, SignerError> {
+    rlp_stream(transaction, Some(signature))
+}
+
+/// Keccak-256 digest of the unsigned transaction, signed by the sender.
+///
+/// # Arguments
+///
+/// * `transaction` - an EIP-1559 transaction
+///
+pub fn eth_transaction_digest(transaction: &EthTransactionAPI) -> Result<[u8; 32], SignerError> {
+    let digest = Keccak256::digest(&eth_transaction_serialize(transaction)?);
+
+    let mut result = [0u8; 32];
+    result.copy_from_slice(&digest);
+
+    Ok(result)
+}
+
+/// Sign an EIP-1559 transaction with a secp256k1 `Signer`. Return a delegated signature.
+///
+/// # Arguments
+///
+/// * `transaction` - an EIP-1559 transaction
+/// * `signer` - a secp256k1 `Signer`
+///
+pub fn eth_transaction_sign_with_signer<S: Signer + ?Sized>(
+    transaction: &EthTransactionAPI,
+    signer: &S,
+) -> Result<Signature, SignerError> {
+    if signer.key_type() != KeyType::Secp256k1 {
+        return Err(SignerError::GenericString(
+            "Only secp256k1 keys can sign Ethereum transactions".to_string(),
+        ));
+    }
+
+    match signer.sign_digest(&eth_transaction_digest(transaction)?)? {
+        Signature::SignatureSECP256K1(signature) => Ok(Signature::SignatureDelegated(signature)),
+        _ => Err(SignerError::GenericString(
+            "Expected a secp256k1 signature".to_string(),
+        )),
+    }
+}
+
+/// Sign an EIP-1559 transaction. Return a delegated signature.
+///
+/// # Arguments
+///
+/// * `transaction` - an EIP-1559 transaction
+/// * `private_key` - a secp256k1 `PrivateKey` (e.g from `key_derive`)
+///
+pub fn eth_transaction_sign(
+    transaction: &EthTransactionAPI,
+    private_key: &PrivateKey,
+) -> Result<Signature, SignerError> {
+    let signer = key_recover(private_key, false)?;
+
+    eth_transaction_sign_with_signer(transaction, &signer)
+}
+
+fn cbor_bytes_params(input: &[u8]) -> Result<String, SignerError> {
+    Ok(base64::encode(to_vec(&serde_bytes::Bytes::new(input))?))
+}
+
+/// Convert an EIP-1559 transaction into the filecoin message executed on chain.
+///
+/// # Arguments
+///
+/// * `transaction` - an EIP-1559 transaction
+/// * `from` - the `f410` address of the sender
+///
+pub fn eth_transaction_to_unsigned_message(
+    transaction: &EthTransactionAPI,
+    from: &str,
+) -> Result<UnsignedMessageAPI, SignerError> {
+    let from_address = DelegatedAddress::from_str(from)?;
+    if from_address.namespace() != EAM_NAMESPACE {
+        return Err(SignerError::GenericString(
+            "Ethereum transactions must be sent from a f410 address".to_string(),
+        ));
+    }
+
+    let input = decode_hex(&transaction.input)?;
+
+    let (to, method, params) = match &transaction.to {
+        Some(_) => {
+            let mut to = DelegatedAddress::new(EAM_NAMESPACE, &to_bytes(&transaction.to)?)?;
+            to.set_network(from_address.network());
+
+            let params = if input.is_empty() {
+                String::new()
+            } else {
+                cbor_bytes_params(&input)?
+            };
+
+            (to.to_string(), METHOD_INVOKE_CONTRACT, params)
+        }
+        None => {
+            let mut to = Address::new_id(EAM_NAMESPACE);
+            to.set_network(from_address.network());
+
+            (
+                to.to_string(),
+                METHOD_CREATE_EXTERNAL,
+                cbor_bytes_params(&input)?,
+            )
+        }
+    };
+
+    Ok(UnsignedMessageAPI {
+        to,
+        from: from.to_string(),
+        nonce: transaction.nonce,
+        value: transaction.value.clone(),
+        gas_limit: i64::try_from(transaction.gas_limit)
+            .map_err(|err| SignerError::GenericString(err.to_string()))?,
+        gas_fee_cap: transaction.max_fee_per_gas.clone(),
+        gas_premium: transaction.max_priority_fee_per_gas.clone(),
+        method,
+        params,
+    })
+}
+
+/// Convert a signed EIP-1559 transaction into a filecoin signed message with a delegated
+/// signature. The `from` address is recovered from the signature.
+///
+/// # Arguments
+///
+/// * `transaction` - an EIP-1559 transaction
+/// * `signature` - the delegated (RSV) signature of the transaction
+/// * `testnet` - specify the network of the addresses, `true` if testnet else `false` for mainnet
+///
+pub fn eth_transaction_to_signed_message(
+    transaction: &EthTransactionAPI,
+    signature: &SignatureSECP256K1,
+    testnet: bool,
+) -> Result<SignedMessageAPI, SignerError> {
+    let public_key =
+        recover_secp256k1_public_key(signature, &eth_transaction_digest(transaction)?)?;
+    let from = f410_address_from_public_key(&public_key.serialize(), testnet)?;
+
+    Ok(SignedMessageAPI {
+        message: eth_transaction_to_unsigned_message(transaction, &from)?,
+        signature: SignatureAPI {
+            sig_type: SigTypes::SigTypeDelegated as u8,
+            data: signature.0.to_vec(),
+        },
+    })
+}
+
+/// Convert a filecoin signed message with a delegated signature back into the EIP-1559
+/// transaction that was signed.
+///
+/// # Arguments
+///
+/// * `signed_message` - a signed message from a `f410` address
+/// * `chain_id` - the EIP-155 chain id (e.g `MAINNET_CHAIN_ID`)
+///
+pub fn eth_transaction_from_signed_message(
+    signed_message: &SignedMessageAPI,
+    chain_id: u64,
+) -> Result<(EthTransactionAPI, SignatureSECP256K1), SignerError> {
+    if signed_message.signature.sig_type != SigTypes::SigTypeDelegated as u8 {
+        return Err(SignerError::GenericString(
+            "Expected a delegated signature".to_string(),
+        ));
+    }
+    let signature = SignatureSECP256K1::try_from(signed_message.signature.data.clone())?;
+
+    let message = &signed_message.message;
+    let params = base64::decode(&message.params)?;
+    let input: Vec<u8> = if params.is_empty() {
+        Vec::new()
+    } else {
+        from_slice::<serde_bytes::ByteBuf>(&params)?.into_vec()
+    };
+
+    let to = match (AnyAddress::from_str(&message.to)?, message.method) {
+        (AnyAddress::Delegated(to), METHOD_INVOKE_CONTRACT)
+            if to.namespace() == EAM_NAMESPACE && to.subaddress().len() == ETH_ADDRESS_SIZE =>
+        {
+            Some(format!("0x{}", hex::encode(to.subaddress())))
+        }
+        (AnyAddress::Address(to), METHOD_CREATE_EXTERNAL)
+            if to.to_bytes() == Address::new_id(EAM_NAMESPACE).to_bytes() =>
+        {
+            None
+        }
+        _ => {
+            return Err(SignerError::GenericString(
+                "Message is not an Ethereum transaction".to_string(),
+            ))
+        }
+    };
+
+    let transaction = EthTransactionAPI {
+        chain_id,
+        nonce: message.nonce,
+        to,
+        value: message.value.clone(),
+        max_fee_per_gas: message.gas_fee_cap.clone(),
+        max_priority_fee_per_gas: message.gas_premium.clone(),
+        gas_limit: u64::try_from(message.gas_limit)
+            .map_err(|err| SignerError::GenericString(err.to_string()))?,
+        input: format!("0x{}", hex::encode(input)),
+    };
+
+    Ok((transaction, signature))
+}
+
+/// Verify a signed message with a delegated signature: the signature must be valid for the
+/// Ethereum transaction and match the `from` address. Return a boolean.
+///
+/// # Arguments
+///
+/// * `signed_message` - a signed message from a `f410` address
+/// * `chain_id` - the EIP-155 chain id (e.g `MAINNET_CHAIN_ID`)
+///
+pub fn verify_eth_signed_message(
+    signed_message: &SignedMessageAPI,
+    chain_id: u64,
+) -> Result<bool, SignerError> {
+    let (transaction, signature) = eth_transaction_from_signed_message(signed_message, chain_id)?;
+    let digest = eth_transaction_digest(&transaction)?;
+
+    let public_key = recover_secp256k1_public_key(&signature, &digest)?;
+
+    // Compare regardless of the network
+    let from = DelegatedAddress::from_str(&signed_message.message.from)?;
+    if from.namespace() != EAM_NAMESPACE
+        || from.subaddress() != eth_address_from_public_key(&public_key.serialize())?
+    {
+        return Ok(false);
+    }
+
+    let signature_rs = secp256k1::Signature::parse_slice(&signature.0[..64])?;
+
+    Ok(secp256k1::verify(
+        &secp256k1::Message::parse(&digest),
+        &signature_rs,
+        &public_key,
+    ))
+}
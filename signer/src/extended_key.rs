@@ -3,7 +3,8 @@ use secp256k1::{PublicKey, SecretKey};
 
 use crate::error::SignerError;
 use hmac::{Hmac, Mac, NewMac};
-use sha2::Sha512;
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
 use std::convert::TryFrom;
 use std::fmt;
 use zeroize::Zeroize;
@@ -12,25 +13,38 @@ use zx_bip44::BIP44Path;
 const HMAC_SEED: &[u8; 12] = b"Bitcoin seed";
 const HARDENED_BIT: u32 = 1 << 31;
 
-#[derive(Zeroize, Debug)]
+const XPRV_VERSION: [u8; 4] = [0x04, 0x88, 0xAD, 0xE4];
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xB2, 0x1E];
+const SERIALIZED_KEY_SIZE: usize = 78;
+const CHECKSUM_SIZE: usize = 4;
+
+#[derive(Zeroize)]
 #[zeroize(drop)]
 struct ChainCode([u8; 32]);
 
 pub struct ExtendedSecretKey {
     secret_key: SecretKey,
     chain_code: ChainCode,
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+}
+
+/// Extended public key, only allows non-hardened child derivation
+#[derive(Clone)]
+pub struct ExtendedPublicKey {
+    public_key: PublicKey,
+    chain_code: [u8; 32],
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
 }
 
 type HmacSha512 = Hmac<Sha512>;
 
 impl fmt::Display for ExtendedSecretKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "SecretKey/ChainCode:  {:?}/{:?}",
-            hex::encode(&self.secret_key()),
-            hex::encode(&self.chain_code.0)
-        )
+        write!(f, "ExtendedSecretKey(<redacted>)")
     }
 }
 
@@ -61,6 +75,9 @@ impl ExtendedSecretKey {
         Ok(ExtendedSecretKey {
             secret_key,
             chain_code: tmp,
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
         })
     }
 
@@ -107,7 +124,66 @@ impl ExtendedSecretKey {
         let mut child_secret_key = self.secret_key.clone();
         child_secret_key.tweak_add_assign(&SecretKey::parse_slice(secret_key_shift)?)?;
 
-        ExtendedSecretKey::new(child_secret_key, &child_chain_code)
+        let mut child = ExtendedSecretKey::new(child_secret_key, &child_chain_code)?;
+        child.depth = self
+            .depth
+            .checked_add(1)
+            .ok_or_else(|| SignerError::GenericString("Maximum depth reached".to_string()))?;
+        child.parent_fingerprint = fingerprint(&self.public_key_compressed());
+        child.child_number = child_index;
+
+        Ok(child)
+    }
+
+    pub fn derive_path(&self, path: &[u32]) -> Result<ExtendedSecretKey, SignerError> {
+        let (first, rest) = path
+            .split_first()
+            .ok_or_else(|| SignerError::GenericString("Empty derivation path".to_string()))?;
+
+        let mut child = self.derive_child_key(*first)?;
+        for index in rest {
+            child = child.derive_child_key(*index)?;
+        }
+
+        Ok(child)
+    }
+
+    pub fn extended_public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            public_key: PublicKey::from_secret_key(&self.secret_key),
+            chain_code: self.chain_code.0,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+        }
+    }
+
+    /// Serialize as a BIP32 `xprv` string
+    pub fn to_xprv(&self) -> String {
+        let mut key_data = [0u8; 33];
+        key_data[1..].copy_from_slice(&self.secret_key());
+
+        let xprv = serialize_extended_key(
+            XPRV_VERSION,
+            self.depth,
+            self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code.0,
+            &key_data,
+        );
+        key_data.zeroize();
+
+        xprv
+    }
+
+    /// Parse a BIP32 `xprv` string
+    pub fn from_xprv(xprv: &str) -> Result<ExtendedSecretKey, SignerError> {
+        let mut data = deserialize_extended_key(xprv, XPRV_VERSION)?;
+
+        let key = parse_xprv_data(&data);
+        data.zeroize();
+
+        key
     }
 
     pub fn derive_bip44(&self, path: &BIP44Path) -> Result<ExtendedSecretKey, SignerError> {
@@ -121,9 +197,282 @@ impl ExtendedSecretKey {
     }
 }
 
+impl ExtendedPublicKey {
+    #[inline]
+    pub fn public_key(&self) -> [u8; FULL_PUBLIC_KEY_SIZE] {
+        self.public_key.serialize()
+    }
+
+    #[inline]
+    pub fn public_key_compressed(&self) -> [u8; COMPRESSED_PUBLIC_KEY_SIZE] {
+        self.public_key.serialize_compressed()
+    }
+
+    #[inline]
+    pub fn chain_code(&self) -> [u8; 32] {
+        self.chain_code
+    }
+
+    pub fn derive_child_key(&self, child_index: u32) -> Result<ExtendedPublicKey, SignerError> {
+        if child_index & HARDENED_BIT != 0 {
+            return Err(SignerError::GenericString(
+                "Cannot derive a hardened child from an extended public key".to_string(),
+            ));
+        }
+
+        let mut hmac = Hmac::<Sha512>::new_varkey(&self.chain_code)?;
+        hmac.update(&self.public_key_compressed());
+        hmac.update(&child_index.to_be_bytes());
+
+        let hmac_result = hmac.finalize().into_bytes();
+        let (public_key_shift, child_chain_code) = hmac_result.split_at(32);
+
+        let mut child_public_key = self.public_key.clone();
+        child_public_key.tweak_add_assign(&SecretKey::parse_slice(public_key_shift)?)?;
+
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(child_chain_code);
+
+        Ok(ExtendedPublicKey {
+            public_key: child_public_key,
+            chain_code,
+            depth: self
+                .depth
+                .checked_add(1)
+                .ok_or_else(|| SignerError::GenericString("Maximum depth reached".to_string()))?,
+            parent_fingerprint: fingerprint(&self.public_key_compressed()),
+            child_number: child_index,
+        })
+    }
+
+    pub fn derive_path(&self, path: &[u32]) -> Result<ExtendedPublicKey, SignerError> {
+        let mut child = self.clone();
+        for index in path {
+            child = child.derive_child_key(*index)?;
+        }
+
+        Ok(child)
+    }
+
+    /// Serialize as a BIP32 `xpub` string
+    pub fn to_xpub(&self) -> String {
+        serialize_extended_key(
+            XPUB_VERSION,
+            self.depth,
+            self.parent_fingerprint,
+            self.child_number,
+            &self.chain_code,
+            &self.public_key_compressed(),
+        )
+    }
+
+    /// Parse a BIP32 `xpub` string
+    pub fn from_xpub(xpub: &str) -> Result<ExtendedPublicKey, SignerError> {
+        let data = deserialize_extended_key(xpub, XPUB_VERSION)?;
+
+        let public_key = PublicKey::parse_slice(&data[45..78], None)?;
+
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&data[13..45]);
+        let mut parent_fingerprint = [0u8; 4];
+        parent_fingerprint.copy_from_slice(&data[5..9]);
+        let mut child_number = [0u8; 4];
+        child_number.copy_from_slice(&data[9..13]);
+
+        Ok(ExtendedPublicKey {
+            public_key,
+            chain_code,
+            depth: data[4],
+            parent_fingerprint,
+            child_number: u32::from_be_bytes(child_number),
+        })
+    }
+}
+
+fn parse_xprv_data(data: &[u8; SERIALIZED_KEY_SIZE]) -> Result<ExtendedSecretKey, SignerError> {
+    if data[45] != 0 {
+        return Err(SignerError::GenericString(
+            "Invalid xprv key data".to_string(),
+        ));
+    }
+
+    let mut key = ExtendedSecretKey::new(SecretKey::parse_slice(&data[46..78])?, &data[13..45])?;
+    key.depth = data[4];
+    key.parent_fingerprint.copy_from_slice(&data[5..9]);
+
+    let mut child_number = [0u8; 4];
+    child_number.copy_from_slice(&data[9..13]);
+    key.child_number = u32::from_be_bytes(child_number);
+
+    Ok(key)
+}
+
+/// Parse a derivation path of any depth (e.g `m/44'/461'/0'`)
+pub fn parse_derivation_path(path: &str) -> Result<Vec<u32>, SignerError> {
+    let mut nodes = path.split('/');
+
+    if nodes.next() != Some("m") {
+        return Err(SignerError::GenericString(
+            "Invalid path: should start with `m`".to_string(),
+        ));
+    }
+
+    nodes
+        .map(|node| match node.strip_suffix('\'') {
+            Some(hardened) => Ok(hardened.parse::<u32>()? | HARDENED_BIT),
+            None => Ok(node.parse::<u32>()?),
+        })
+        .collect()
+}
+
+/// Derivation path template with a `{i}` placeholder as last index (e.g `m/44'/461'/0'/0/{i}`)
+pub struct PathTemplate {
+    pub parent: Vec<u32>,
+    pub hardened: bool,
+}
+
+impl PathTemplate {
+    pub fn from_string(template: &str) -> Result<Self, SignerError> {
+        let separator = template.rfind('/').ok_or_else(|| {
+            SignerError::GenericString("Invalid path template: missing `{i}`".to_string())
+        })?;
+        let (parent, last) = (&template[..separator], &template[separator + 1..]);
+
+        let hardened = match last {
+            "{i}" => false,
+            "{i}'" => true,
+            _ => {
+                return Err(SignerError::GenericString(
+                    "Invalid path template: last index should be `{i}` or `{i}'`".to_string(),
+                ))
+            }
+        };
+
+        Ok(PathTemplate {
+            parent: parse_derivation_path(parent)?,
+            hardened,
+        })
+    }
+
+    /// Child index for `{i}`
+    pub fn index(&self, i: u32) -> Result<u32, SignerError> {
+        if i & HARDENED_BIT != 0 {
+            return Err(SignerError::GenericString(format!(
+                "Index {} out of range",
+                i
+            )));
+        }
+
+        if self.hardened {
+            Ok(i | HARDENED_BIT)
+        } else {
+            Ok(i)
+        }
+    }
+
+    /// The coin type `1` is used for testnet
+    pub fn is_testnet(&self) -> bool {
+        self.parent.get(1) == Some(&(1 | HARDENED_BIT))
+    }
+}
+
+/// Parse a relative path of non-hardened indexes (e.g `0/5`)
+pub fn parse_public_path(path: &str) -> Result<Vec<u32>, SignerError> {
+    path.split('/')
+        .map(|node| {
+            let index = node.parse::<u32>()?;
+            if index & HARDENED_BIT != 0 {
+                return Err(SignerError::GenericString(
+                    "Hardened index not allowed in public derivation".to_string(),
+                ));
+            }
+            Ok(index)
+        })
+        .collect()
+}
+
+fn fingerprint(public_key_compressed: &[u8]) -> [u8; 4] {
+    let hash = Ripemd160::digest(&Sha256::digest(public_key_compressed));
+
+    let mut fingerprint = [0u8; 4];
+    fingerprint.copy_from_slice(&hash[..4]);
+    fingerprint
+}
+
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    let hash = Sha256::digest(&Sha256::digest(data));
+
+    let mut checksum = [0u8; CHECKSUM_SIZE];
+    checksum.copy_from_slice(&hash[..CHECKSUM_SIZE]);
+    checksum
+}
+
+fn serialize_extended_key(
+    version: [u8; 4],
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: &[u8; 32],
+    key_data: &[u8; 33],
+) -> String {
+    let mut data = Vec::with_capacity(SERIALIZED_KEY_SIZE + CHECKSUM_SIZE);
+    data.extend_from_slice(&version);
+    data.push(depth);
+    data.extend_from_slice(&parent_fingerprint);
+    data.extend_from_slice(&child_number.to_be_bytes());
+    data.extend_from_slice(chain_code);
+    data.extend_from_slice(key_data);
+    let checksum = checksum(&data);
+    data.extend_from_slice(&checksum);
+
+    let encoded = bs58::encode(&data).into_string();
+    data.zeroize();
+
+    encoded
+}
+
+fn deserialize_extended_key(
+    encoded: &str,
+    version: [u8; 4],
+) -> Result<[u8; SERIALIZED_KEY_SIZE], SignerError> {
+    let mut decoded = bs58::decode(encoded)
+        .into_vec()
+        .map_err(|e| SignerError::GenericString(e.to_string()))?;
+
+    if decoded.len() != SERIALIZED_KEY_SIZE + CHECKSUM_SIZE {
+        decoded.zeroize();
+        return Err(SignerError::GenericString(
+            "Invalid extended key length".to_string(),
+        ));
+    }
+
+    let mut data = [0u8; SERIALIZED_KEY_SIZE];
+    data.copy_from_slice(&decoded[..SERIALIZED_KEY_SIZE]);
+    let valid_checksum = checksum(&data)[..] == decoded[SERIALIZED_KEY_SIZE..];
+    decoded.zeroize();
+
+    if !valid_checksum {
+        data.zeroize();
+        return Err(SignerError::GenericString(
+            "Invalid extended key checksum".to_string(),
+        ));
+    }
+    if data[..4] != version {
+        data.zeroize();
+        return Err(SignerError::GenericString(
+            "Invalid extended key version".to_string(),
+        ));
+    }
+
+    Ok(data)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::extended_key::ExtendedSecretKey;
+    use crate::extended_key::{
+        parse_derivation_path, parse_public_path, ExtendedPublicKey, ExtendedSecretKey,
+        PathTemplate,
+    };
     use bip39::{Language, Mnemonic, Seed};
     use hex::encode;
     use std::convert::TryFrom;
@@ -220,4 +569,77 @@ mod tests {
         assert_eq!(result.0[3], 0);
         assert_eq!(result.0[4], 0);
     }
+
+    // BIP32 test vector 1
+    const BIP32_SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+    #[test]
+    fn serialize_master_key() {
+        let seed = hex::decode(BIP32_SEED).unwrap();
+        let master = ExtendedSecretKey::try_from(seed.as_slice()).unwrap();
+
+        assert_eq!(master.to_xprv(), "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi");
+        assert_eq!(master.extended_public_key().to_xpub(), "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8");
+    }
+
+    #[test]
+    fn serialize_derived_key() {
+        let seed = hex::decode(BIP32_SEED).unwrap();
+        let master = ExtendedSecretKey::try_from(seed.as_slice()).unwrap();
+
+        let path = parse_derivation_path("m/0'/1").unwrap();
+        let esk = master.derive_path(&path).unwrap();
+
+        assert_eq!(esk.to_xprv(), "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs");
+        assert_eq!(esk.extended_public_key().to_xpub(), "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ");
+
+        let parsed = ExtendedSecretKey::from_xprv(&esk.to_xprv()).unwrap();
+        assert_eq!(parsed.secret_key(), esk.secret_key());
+        assert_eq!(parsed.to_xprv(), esk.to_xprv());
+    }
+
+    #[test]
+    fn derive_public_child() {
+        // m/0'
+        let epk = ExtendedPublicKey::from_xpub("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw").unwrap();
+
+        // m/0'/1
+        let child = epk.derive_child_key(1).unwrap();
+        assert_eq!(child.to_xpub(), "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ");
+        assert_eq!(
+            encode(child.public_key().to_vec()),
+            "04501e454bf00751f24b1b489aa925215d66af2234e3891c3b21a52bedb3cd711c008794c1df8131b9ad1e1359965b3f3ee2feef0866be693729772be14be881ab"
+        );
+
+        assert!(epk.derive_child_key(1 | HARDENED_BIT).is_err());
+        assert!(parse_public_path("0/1'").is_err());
+    }
+
+    #[test]
+    fn create_path_template() {
+        let template = PathTemplate::from_string("m/44'/1'/0'/0/{i}").unwrap();
+
+        assert_eq!(
+            template.parent,
+            vec![44 | HARDENED_BIT, 1 | HARDENED_BIT, HARDENED_BIT, 0]
+        );
+        assert!(template.is_testnet());
+        assert_eq!(template.index(3).unwrap(), 3);
+        assert!(template.index(HARDENED_BIT).is_err());
+
+        let template = PathTemplate::from_string("m/44'/461'/{i}'").unwrap();
+        assert!(!template.is_testnet());
+        assert_eq!(template.index(3).unwrap(), 3 | HARDENED_BIT);
+
+        assert!(PathTemplate::from_string("m/44'/461'/{i}/0").is_err());
+        assert!(PathTemplate::from_string("44'/461'/0/{i}").is_err());
+    }
+
+    #[test]
+    fn invalid_extended_keys() {
+        // Bad checksum
+        assert!(ExtendedPublicKey::from_xpub("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet9").is_err());
+        // xprv is not an xpub
+        assert!(ExtendedPublicKey::from_xpub("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi").is_err());
+    }
 }
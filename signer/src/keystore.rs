@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chacha20poly1305::aead::generic_array::GenericArray;
+use chacha20poly1305::aead::{Aead, NewAead, Payload};
+use chacha20poly1305::XChaCha20Poly1305;
+use forest_address::Address;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::error::SignerError;
+use crate::lotus::LotusKeyInfo;
+use crate::{ExtendedKey, Mnemonic};
+
+/// Current version of the keystore entry format
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const KDF_SCRYPT: &str = "scrypt";
+const CIPHER_XCHACHA20POLY1305: &str = "xchacha20poly1305";
+const SALT_SIZE: usize = 32;
+const NONCE_SIZE: usize = 24;
+const ENCRYPTION_KEY_SIZE: usize = 32;
+
+/// Scrypt limits, entries are read from untrusted files
+const MAX_SCRYPT_LOG_N: u8 = 20;
+const MAX_SCRYPT_R: u32 = 32;
+const MAX_SCRYPT_P: u32 = 16;
+/// Maximum scrypt memory usage (`128 * r * 2^log_n` bytes)
+const MAX_SCRYPT_MEMORY: u64 = 256 * 1024 * 1024;
+
+/// Scrypt parameters
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl KdfParams {
+    /// Reject parameters above the scrypt limits
+    fn check(&self) -> Result<(), SignerError> {
+        if self.log_n > MAX_SCRYPT_LOG_N
+            || self.r > MAX_SCRYPT_R
+            || self.p > MAX_SCRYPT_P
+            || 128 * u64::from(self.r) * (1u64 << self.log_n) > MAX_SCRYPT_MEMORY
+        {
+            return Err(SignerError::GenericString(
+                "Scrypt parameters are too expensive".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KeystoreCrypto {
+    pub kdf: String,
+    #[serde(rename = "kdfparams")]
+    pub kdf_params: KdfParams,
+    /// Salt as base64 string
+    pub salt: String,
+    pub cipher: String,
+    /// Nonce as base64 string
+    pub nonce: String,
+    /// Encrypted key as base64 string
+    #[serde(rename = "ciphertext")]
+    pub cipher_text: String,
+}
+
+/// Encrypted keystore entry (versioned JSON format)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KeystoreEntry {
+    pub version: u32,
+    pub address: String,
+    pub crypto: KeystoreCrypto,
+}
+
+/// Secret stored in a keystore entry
+#[derive(Deserialize, Serialize)]
+struct KeystoreSecret {
+    #[serde(flatten)]
+    key_info: LotusKeyInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mnemonic: Option<String>,
+}
+
+impl Drop for KeystoreSecret {
+    fn drop(&mut self) {
+        self.mnemonic.zeroize();
+    }
+}
+
+/// Key unlocked from the keystore
+pub struct UnlockedKey {
+    pub extended_key: ExtendedKey,
+    pub mnemonic: Option<Mnemonic>,
+}
+
+impl KeystoreEntry {
+    /// Encrypt a key (and optionally the mnemonic it was derived from) with a password
+    ///
+    /// # Arguments
+    ///
+    /// * `extended_key` - The key to encrypt
+    /// * `mnemonic` - The mnemonic to store along the key, if any
+    /// * `password` - The password used to derive the encryption key
+    /// * `kdf_params` - Scrypt parameters
+    ///
+    pub fn encrypt(
+        extended_key: &ExtendedKey,
+        mnemonic: Option<&str>,
+        password: &str,
+        kdf_params: KdfParams,
+    ) -> Result<KeystoreEntry, SignerError> {
+        let secret = KeystoreSecret {
+            key_info: LotusKeyInfo::from(extended_key),
+            mnemonic: mnemonic.map(|m| m.to_string()),
+        };
+        let mut plain_text = serde_json::to_vec(&secret)?;
+
+        let mut salt = [0u8; SALT_SIZE];
+        getrandom::getrandom(&mut salt)?;
+        let mut nonce = [0u8; NONCE_SIZE];
+        getrandom::getrandom(&mut nonce)?;
+
+        let cipher_text = aead_cipher(password, &salt, kdf_params)?.encrypt(
+            GenericArray::from_slice(&nonce),
+            Payload {
+                msg: &plain_text,
+                aad: extended_key.address.as_bytes(),
+            },
+        );
+        plain_text.zeroize();
+
+        let cipher_text = cipher_text
+            .map_err(|_| SignerError::GenericString("Key encryption failed".to_string()))?;
+
+        Ok(KeystoreEntry {
+            version: KEYSTORE_VERSION,
+            address: extended_key.address.clone(),
+            crypto: KeystoreCrypto {
+                kdf: KDF_SCRYPT.to_string(),
+                kdf_params,
+                salt: base64::encode(&salt),
+                cipher: CIPHER_XCHACHA20POLY1305.to_string(),
+                nonce: base64::encode(&nonce),
+                cipher_text: base64::encode(&cipher_text),
+            },
+        })
+    }
+
+    /// Decrypt the entry with the password
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password used to encrypt the entry
+    ///
+    pub fn decrypt(&self, password: &str) -> Result<UnlockedKey, SignerError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(SignerError::GenericString(format!(
+                "Unsupported keystore version {}",
+                self.version
+            )));
+        }
+        if self.crypto.kdf != KDF_SCRYPT || self.crypto.cipher != CIPHER_XCHACHA20POLY1305 {
+            return Err(SignerError::GenericString(
+                "Unsupported keystore kdf or cipher".to_string(),
+            ));
+        }
+
+        let salt = base64::decode(&self.crypto.salt)?;
+        let nonce = base64::decode(&self.crypto.nonce)?;
+        let cipher_text = base64::decode(&self.crypto.cipher_text)?;
+
+        if nonce.len() != NONCE_SIZE {
+            return Err(SignerError::GenericString(
+                "Invalid nonce length".to_string(),
+            ));
+        }
+
+        let mut plain_text = aead_cipher(password, &salt, self.crypto.kdf_params)?
+            .decrypt(
+                GenericArray::from_slice(&nonce),
+                Payload {
+                    msg: &cipher_text,
+                    aad: self.address.as_bytes(),
+                },
+            )
+            .map_err(|_| SignerError::InvalidPassword)?;
+
+        let secret: Result<KeystoreSecret, _> = serde_json::from_slice(&plain_text);
+        plain_text.zeroize();
+        let secret = secret?;
+
+        let extended_key = secret
+            .key_info
+            .to_extended_key(self.address.starts_with('t'))?;
+
+        if extended_key.address != self.address {
+            return Err(SignerError::GenericString(
+                "Keystore entry doesn't match the address".to_string(),
+            ));
+        }
+
+        Ok(UnlockedKey {
+            extended_key,
+            mnemonic: secret.mnemonic.clone().map(Mnemonic),
+        })
+    }
+}
+
+fn aead_cipher(
+    password: &str,
+    salt: &[u8],
+    kdf_params: KdfParams,
+) -> Result<XChaCha20Poly1305, SignerError> {
+    kdf_params.check()?;
+
+    let params = scrypt::ScryptParams::new(kdf_params.log_n, kdf_params.r, kdf_params.p)
+        .map_err(|_| SignerError::GenericString("Invalid scrypt parameters".to_string()))?;
+
+    let mut key = [0u8; ENCRYPTION_KEY_SIZE];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+        .map_err(|_| SignerError::GenericString("Key derivation failed".to_string()))?;
+
+    let cipher = XChaCha20Poly1305::new(GenericArray::from_slice(&key));
+    key.zeroize();
+
+    Ok(cipher)
+}
+
+/// Storage backend of a keystore. Entries are serialized JSON strings keyed by address.
+pub trait KeystoreStorage {
+    fn load(&self, address: &str) -> Result<Option<String>, SignerError>;
+    fn store(&mut self, address: &str, entry: &str) -> Result<(), SignerError>;
+    fn addresses(&self) -> Result<Vec<String>, SignerError>;
+}
+
+/// In memory storage
+#[derive(Default)]
+pub struct MemoryStorage(pub HashMap<String, String>);
+
+impl KeystoreStorage for MemoryStorage {
+    fn load(&self, address: &str) -> Result<Option<String>, SignerError> {
+        Ok(self.0.get(address).cloned())
+    }
+
+    fn store(&mut self, address: &str, entry: &str) -> Result<(), SignerError> {
+        self.0.insert(address.to_string(), entry.to_string());
+        Ok(())
+    }
+
+    fn addresses(&self) -> Result<Vec<String>, SignerError> {
+        Ok(self.0.keys().cloned().collect())
+    }
+}
+
+/// Directory storage, one `<address>.json` file per key
+pub struct DirectoryStorage {
+    pub path: PathBuf,
+}
+
+impl DirectoryStorage {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        DirectoryStorage { path: path.into() }
+    }
+
+    /// Path of the entry of an address. Only valid addresses in their canonical form are accepted,
+    /// so the path can't point outside of the storage directory.
+    fn entry_path(&self, address: &str) -> Result<PathBuf, SignerError> {
+        if Address::from_str(address)?.to_string() != address {
+            return Err(SignerError::GenericString(format!(
+                "Invalid address {}",
+                address
+            )));
+        }
+
+        Ok(self.path.join(format!("{}.json", address)))
+    }
+}
+
+impl KeystoreStorage for DirectoryStorage {
+    fn load(&self, address: &str) -> Result<Option<String>, SignerError> {
+        let entry_path = self.entry_path(address)?;
+
+        if !entry_path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(fs::read_to_string(entry_path)?))
+    }
+
+    fn store(&mut self, address: &str, entry: &str) -> Result<(), SignerError> {
+        let entry_path = self.entry_path(address)?;
+        fs::create_dir_all(&self.path)?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(&entry_path)?;
+        // The mode only applies to new files
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(entry.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn addresses(&self) -> Result<Vec<String>, SignerError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut addresses = Vec::new();
+        for entry in fs::read_dir(&self.path)? {
+            let file_name = entry?.file_name();
+            if let Some(address) = file_name.to_str().and_then(|n| n.strip_suffix(".json")) {
+                addresses.push(address.to_string());
+            }
+        }
+
+        Ok(addresses)
+    }
+}
+
+/// Password-encrypted keystore
+pub struct Keystore<S: KeystoreStorage> {
+    storage: S,
+    kdf_params: KdfParams,
+}
+
+impl<S: KeystoreStorage> Keystore<S> {
+    pub fn new(storage: S) -> Self {
+        Keystore {
+            storage,
+            kdf_params: KdfParams::default(),
+        }
+    }
+
+    pub fn with_kdf_params(storage: S, kdf_params: KdfParams) -> Self {
+        Keystore {
+            storage,
+            kdf_params,
+        }
+    }
+
+    /// Encrypt and store a key. Fails if a key is already stored for the address.
+    ///
+    /// # Arguments
+    ///
+    /// * `extended_key` - The key to store
+    /// * `mnemonic` - The mnemonic to store along the key, if any
+    /// * `password` - The password protecting the key
+    ///
+    pub fn create(
+        &mut self,
+        extended_key: &ExtendedKey,
+        mnemonic: Option<&str>,
+        password: &str,
+    ) -> Result<(), SignerError> {
+        if self.storage.load(&extended_key.address)?.is_some() {
+            return Err(SignerError::GenericString(format!(
+                "Key already exists in keystore for address {}",
+                extended_key.address
+            )));
+        }
+
+        let entry = KeystoreEntry::encrypt(extended_key, mnemonic, password, self.kdf_params)?;
+
+        self.storage
+            .store(&extended_key.address, &serde_json::to_string(&entry)?)
+    }
+
+    /// Decrypt the key stored for an address. The stored entry and the decrypted key must match the
+    /// address.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the key
+    /// * `password` - The password protecting the key
+    ///
+    pub fn unlock(&self, address: &str, password: &str) -> Result<UnlockedKey, SignerError> {
+        let unlocked_key = self.entry(address)?.decrypt(password)?;
+
+        if unlocked_key.extended_key.address != address {
+            return Err(SignerError::GenericString(
+                "Keystore entry doesn't match the address".to_string(),
+            ));
+        }
+
+        Ok(unlocked_key)
+    }
+
+    /// Re-encrypt the key stored for an address with a new password
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the key
+    /// * `old_password` - The current password
+    /// * `new_password` - The new password
+    ///
+    pub fn change_password(
+        &mut self,
+        address: &str,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), SignerError> {
+        let unlocked_key = self.unlock(address, old_password)?;
+
+        let entry = KeystoreEntry::encrypt(
+            &unlocked_key.extended_key,
+            unlocked_key.mnemonic.as_ref().map(Mnemonic::export_phrase),
+            new_password,
+            self.kdf_params,
+        )?;
+
+        self.storage.store(address, &serde_json::to_string(&entry)?)
+    }
+
+    /// List the addresses stored in the keystore
+    pub fn list(&self) -> Result<Vec<String>, SignerError> {
+        let mut addresses = self.storage.addresses()?;
+        addresses.sort();
+
+        Ok(addresses)
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    fn entry(&self, address: &str) -> Result<KeystoreEntry, SignerError> {
+        let entry = self.storage.load(address)?.ok_or_else(|| {
+            SignerError::GenericString(format!("No key in keystore for address {}", address))
+        })?;
+
+        let entry: KeystoreEntry = serde_json::from_str(&entry)?;
+
+        if entry.address != address {
+            return Err(SignerError::GenericString(format!(
+                "Keystore entry of {} is stored for address {}",
+                entry.address, address
+            )));
+        }
+
+        Ok(entry)
+    }
+}
@@ -2,43 +2,87 @@
 
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::fmt;
 use std::str::FromStr;
 
 use bip39::{Language, MnemonicType, Seed};
 use bls_signatures::Serialize;
 use forest_address::{Address, BLSPublicKey, Network, Protocol};
-use forest_cid::{multihash::MultihashDigest, Cid, Code::Identity};
+use forest_cid::Cid;
 use forest_encoding::blake2b_256;
 use forest_encoding::{from_slice, to_vec};
-use forest_message::{SignedMessage, UnsignedMessage};
 use num_bigint_chainsafe::BigInt;
 use num_traits::FromPrimitive;
 use rayon::prelude::*;
 use secp256k1::util::{
     COMPRESSED_PUBLIC_KEY_SIZE, FULL_PUBLIC_KEY_SIZE, SECRET_KEY_SIZE, SIGNATURE_SIZE,
 };
-use secp256k1::{recover, sign, verify, Message, RecoveryId};
+use secp256k1::{recover, verify, Message, RecoveryId};
+use zeroize::Zeroize;
 use zx_bip44::BIP44Path;
 
-use extras::{multisig, paych, ExecParams, MethodInit, INIT_ACTOR_ADDR};
+use extras::{multisig, paych, ExecParams, ExecReturn, MethodInit, INIT_ACTOR_ADDR};
 
+use crate::address::AnyAddress;
 use crate::api::{
-    MessageParams, MessageTx, MessageTxAPI, MessageTxNetwork, SignatureAPI, SignedMessageAPI,
-    UnsignedMessageAPI,
+    AnyMessageTx, AnySignedMessage, AnyUnsignedMessage, MessageParams, MessageReturn, MessageTxAPI,
+    SigTypes, SignatureAPI, SignedMessageAPI, UnsignedMessageAPI,
 };
+use crate::bls_key::{BLSPath, BLSSecretKey};
+use crate::builder::MessageParamsProvider;
 use crate::error::SignerError;
-use crate::extended_key::ExtendedSecretKey;
+use crate::extended_key::{ExtendedPublicKey, ExtendedSecretKey, PathTemplate};
+use crate::lotus::LotusKeyInfo;
 use crate::signature::{Signature, SignatureBLS, SignatureSECP256K1};
+use crate::signer::Signer;
+use crate::token::{IntoTokenAmount, TokenAmount};
+use crate::validation::validate_message;
 
+pub mod address;
 pub mod api;
+pub mod bls_key;
+pub mod builder;
+pub mod bundle;
 pub mod error;
+pub mod eth;
 pub mod extended_key;
+pub mod keystore;
+pub mod lotus;
 pub mod signature;
+pub mod signer;
+pub mod token;
+pub mod transport;
 pub mod utils;
+pub mod validation;
 
-/// Mnemonic string
+/// Mnemonic string, zeroized on drop
 pub struct Mnemonic(pub String);
 
+impl Mnemonic {
+    /// Export the mnemonic phrase
+    pub fn export_phrase(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for Mnemonic {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Mnemonic(<redacted>)")
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
 /// CBOR message in a buffer
 pub struct CborBuffer(pub Vec<u8>);
 
@@ -52,12 +96,63 @@ pub const SIGNATURE_RECOVERY_SIZE: usize = SIGNATURE_SIZE + 1;
 
 pub const BLS_PUB_LEN: usize = 48;
 
-/// Private key buffer
+/// Private key buffer, zeroized on drop
 pub struct PrivateKey(pub [u8; SECRET_KEY_SIZE]);
 
+impl PrivateKey {
+    /// Export the private key bytes
+    pub fn export_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Export the private key as an hexstring
+    pub fn export_hex(&self) -> String {
+        hex::encode(&self.0)
+    }
+
+    /// Export the private key as a base64 string
+    pub fn export_base64(&self) -> String {
+        base64::encode(&self.0)
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PrivateKey(<redacted>)")
+    }
+}
+
+impl fmt::Display for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
 /// Public key secp256k1 buffer
 pub struct PublicKeySECP256K1(pub [u8; FULL_PUBLIC_KEY_SIZE]);
 
+/// Payload signed with a secp256k1 key
+pub enum SignedPayload<'a> {
+    /// CBOR encoded unsigned message
+    Transaction(&'a CborBuffer),
+    /// CBOR encoded payment channel voucher
+    Voucher(&'a [u8]),
+    /// 32 bytes digest that was signed
+    Digest(&'a [u8; 32]),
+}
+
+/// Public key and address recovered from a signature
+pub struct RecoveredSigner {
+    pub public_key: PublicKeySECP256K1,
+    pub address: String,
+}
+
 pub enum PublicKey {
     PublicKeySECP256K1(PublicKeySECP256K1),
     BLSPublicKey(BLSPublicKey),
@@ -75,13 +170,23 @@ impl PublicKey {
 /// Compressed public key buffer
 pub struct PublicKeyCompressed(pub [u8; COMPRESSED_PUBLIC_KEY_SIZE]);
 
-/// Extended key structure
+/// Extended key structure, the private key is zeroized on drop
 pub struct ExtendedKey {
     pub private_key: PrivateKey,
     pub public_key: PublicKey,
     pub address: String,
 }
 
+impl fmt::Debug for ExtendedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtendedKey")
+            .field("private_key", &self.private_key)
+            .field("public_key", &hex::encode(self.public_key.to_vec()))
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
 #[cfg(feature = "with-ffi-support")]
 ffi_support::implement_into_ffi_by_pointer!(ExtendedKey);
 
@@ -98,14 +203,16 @@ impl TryFrom<String> for PrivateKey {
 impl TryFrom<Vec<u8>> for PrivateKey {
     type Error = SignerError;
 
-    fn try_from(v: Vec<u8>) -> Result<PrivateKey, Self::Error> {
+    fn try_from(mut v: Vec<u8>) -> Result<PrivateKey, Self::Error> {
         if v.len() != SECRET_KEY_SIZE {
+            v.zeroize();
             return Err(SignerError::GenericString("Invalid Key Length".to_string()));
         }
         let mut sk = PrivateKey {
             0: [0; SECRET_KEY_SIZE],
         };
         sk.0.copy_from_slice(&v[..SECRET_KEY_SIZE]);
+        v.zeroize();
         Ok(sk)
     }
 }
@@ -116,6 +223,78 @@ pub fn key_generate_mnemonic() -> Result<Mnemonic, SignerError> {
     Ok(Mnemonic(mnemonic.to_string()))
 }
 
+/// Generates a mnemonic with the given word count and language
+///
+/// # Arguments
+///
+/// * `word_count` - Number of words (12, 15, 18, 21 or 24)
+/// * `language_code` - The mnemonic language (e.g "en")
+/// * `entropy` - Optional caller-provided entropy (e.g dice rolls or an HSM RNG), it must have
+///   exactly the size required by `word_count` (16 bytes for 12 words up to 32 bytes for 24 words).
+///   A random entropy is used if `None`.
+pub fn key_generate_mnemonic_with_options(
+    word_count: usize,
+    language_code: &str,
+    entropy: Option<&[u8]>,
+) -> Result<Mnemonic, SignerError> {
+    let lang = language_from_code(language_code)?;
+    let mnemonic_type = MnemonicType::for_word_count(word_count)
+        .map_err(|_| SignerError::InvalidMnemonicWordCount(word_count))?;
+
+    let mnemonic = match entropy {
+        Some(entropy) => {
+            if entropy.len() * 8 != mnemonic_type.entropy_bits() {
+                return Err(SignerError::InvalidEntropyLength(entropy.len() * 8));
+            }
+
+            bip39::Mnemonic::from_entropy(entropy, lang)
+                .map_err(|err| SignerError::GenericString(err.to_string()))?
+        }
+        None => bip39::Mnemonic::new(mnemonic_type, lang),
+    };
+
+    Ok(Mnemonic(mnemonic.to_string()))
+}
+
+/// Validates a mnemonic (word count, words and checksum)
+///
+/// # Arguments
+///
+/// * `mnemonic` - A string containing the mnemonic words
+/// * `language_code` - The mnemonic language (e.g "en")
+pub fn mnemonic_validate(mnemonic: &str, language_code: &str) -> Result<(), SignerError> {
+    let lang = language_from_code(language_code)?;
+
+    parse_mnemonic(mnemonic, lang)?;
+
+    Ok(())
+}
+
+fn language_from_code(language_code: &str) -> Result<Language, SignerError> {
+    Language::from_language_code(language_code)
+        .ok_or_else(|| SignerError::GenericString("Unknown language code".to_string()))
+}
+
+fn parse_mnemonic(mnemonic: &str, lang: Language) -> Result<bip39::Mnemonic, SignerError> {
+    bip39::Mnemonic::from_phrase(mnemonic, lang).map_err(|err| {
+        match err.downcast_ref::<bip39::ErrorKind>() {
+            Some(bip39::ErrorKind::InvalidChecksum) => SignerError::InvalidMnemonicChecksum,
+            Some(bip39::ErrorKind::InvalidWord) => {
+                let unknown_word = mnemonic
+                    .split_whitespace()
+                    .find(|word| lang.wordmap().get_bits(word).is_err())
+                    .unwrap_or_default();
+
+                SignerError::UnknownMnemonicWord(unknown_word.to_string())
+            }
+            Some(bip39::ErrorKind::InvalidWordLength(count)) => {
+                SignerError::InvalidMnemonicWordCount(*count)
+            }
+            _ => SignerError::GenericString(err.to_string()),
+        }
+    })
+}
+
 fn derive_extended_secret_key(seed: &[u8], path: &str) -> Result<ExtendedSecretKey, SignerError> {
     let master = ExtendedSecretKey::try_from(seed)?;
     let bip44_path = BIP44Path::from_string(path)?;
@@ -124,27 +303,26 @@ fn derive_extended_secret_key(seed: &[u8], path: &str) -> Result<ExtendedSecretK
     Ok(esk)
 }
 
+fn seed_from_mnemonic(
+    mnemonic: &str,
+    password: &str,
+    language_code: &str,
+) -> Result<Seed, SignerError> {
+    let lang = language_from_code(language_code)?;
+    let mnemonic = parse_mnemonic(mnemonic, lang)?;
+
+    Ok(Seed::new(&mnemonic, password))
+}
+
 fn derive_extended_secret_key_from_mnemonic(
     mnemonic: &str,
     path: &str,
     password: &str,
     language_code: &str,
 ) -> Result<ExtendedSecretKey, SignerError> {
-    let lang = Language::from_language_code(language_code);
-
-    match lang {
-        Some(l) => {
-            let mnemonic = bip39::Mnemonic::from_phrase(&mnemonic, l)
-                .map_err(|err| SignerError::GenericString(err.to_string()))?;
-
-            let seed = Seed::new(&mnemonic, password);
+    let seed = seed_from_mnemonic(mnemonic, password, language_code)?;
 
-            derive_extended_secret_key(seed.as_bytes(), path)
-        }
-        None => Err(SignerError::GenericString(
-            "Unknown language code".to_string(),
-        )),
-    }
+    derive_extended_secret_key(seed.as_bytes(), path)
 }
 
 /// Returns a public key, private key and address given a mnemonic, derivation path and a password
@@ -204,6 +382,270 @@ pub fn key_derive_from_seed(seed: &[u8], path: &str) -> Result<ExtendedKey, Sign
     })
 }
 
+fn extended_key_from_secret_key(
+    esk: &ExtendedSecretKey,
+    testnet: bool,
+) -> Result<ExtendedKey, SignerError> {
+    let mut address = Address::new_secp256k1(&esk.public_key().to_vec())?;
+
+    address.set_network(Network::Mainnet);
+    if testnet {
+        address.set_network(Network::Testnet);
+    }
+
+    Ok(ExtendedKey {
+        private_key: PrivateKey(esk.secret_key()),
+        public_key: PublicKey::PublicKeySECP256K1(PublicKeySECP256K1(esk.public_key())),
+        address: address.to_string(),
+    })
+}
+
+fn derive_batch(
+    parent: &ExtendedSecretKey,
+    template: &PathTemplate,
+    start: u32,
+    count: u32,
+) -> Result<Vec<ExtendedKey>, SignerError> {
+    let end = start
+        .checked_add(count)
+        .ok_or_else(|| SignerError::GenericString("Index out of range".to_string()))?;
+
+    (start..end)
+        .into_par_iter()
+        .map(|i| {
+            let esk = parent.derive_child_key(template.index(i)?)?;
+            extended_key_from_secret_key(&esk, template.is_testnet())
+        })
+        .collect()
+}
+
+fn derive_template_parent(
+    seed: &[u8],
+    template: &PathTemplate,
+) -> Result<ExtendedSecretKey, SignerError> {
+    let master = ExtendedSecretKey::try_from(seed)?;
+
+    if template.parent.is_empty() {
+        return Ok(master);
+    }
+
+    master.derive_path(&template.parent)
+}
+
+/// Returns `count` keys derived from a path template starting at index `start`
+///
+/// The seed is computed once and the keys are derived in parallel.
+///
+/// # Arguments
+///
+/// * `mnemonic` - A string containing the mnemonic words
+/// * `path_template` - A derivation path ending with `{i}` or `{i}'` (e.g "m/44'/461'/0'/0/{i}")
+/// * `password` - Password to decrypt seed, if none use and empty string (e.g "")
+/// * `language_code` - The mnemonic language (e.g "en")
+/// * `start` - First index
+/// * `count` - Number of keys
+pub fn key_derive_batch(
+    mnemonic: &str,
+    path_template: &str,
+    password: &str,
+    language_code: &str,
+    start: u32,
+    count: u32,
+) -> Result<Vec<ExtendedKey>, SignerError> {
+    let seed = seed_from_mnemonic(mnemonic, password, language_code)?;
+
+    key_derive_batch_from_seed(seed.as_bytes(), path_template, start, count)
+}
+
+/// Returns `count` keys derived from a path template starting at index `start`
+///
+/// # Arguments
+///
+/// * `seed` - A seed as bytes array
+/// * `path_template` - A derivation path ending with `{i}` or `{i}'` (e.g "m/44'/461'/0'/0/{i}")
+/// * `start` - First index
+/// * `count` - Number of keys
+pub fn key_derive_batch_from_seed(
+    seed: &[u8],
+    path_template: &str,
+    start: u32,
+    count: u32,
+) -> Result<Vec<ExtendedKey>, SignerError> {
+    let template = PathTemplate::from_string(path_template)?;
+    let parent = derive_template_parent(seed, &template)?;
+
+    derive_batch(&parent, &template, start, count)
+}
+
+/// Scan a path template for used accounts, stopping after `gap_limit` consecutive unused addresses.
+/// Returns the keys of the used accounts.
+///
+/// # Arguments
+///
+/// * `mnemonic` - A string containing the mnemonic words
+/// * `path_template` - A derivation path ending with `{i}` or `{i}'` (e.g "m/44'/461'/0'/0/{i}")
+/// * `password` - Password to decrypt seed, if none use and empty string (e.g "")
+/// * `language_code` - The mnemonic language (e.g "en")
+/// * `gap_limit` - Number of consecutive unused addresses before stopping
+/// * `is_used` - Returns `true` if the address has been used
+pub fn key_scan_gap_limit<F>(
+    mnemonic: &str,
+    path_template: &str,
+    password: &str,
+    language_code: &str,
+    gap_limit: u32,
+    is_used: F,
+) -> Result<Vec<ExtendedKey>, SignerError>
+where
+    F: FnMut(&str) -> bool,
+{
+    let seed = seed_from_mnemonic(mnemonic, password, language_code)?;
+
+    key_scan_gap_limit_from_seed(seed.as_bytes(), path_template, gap_limit, is_used)
+}
+
+/// Scan a path template for used accounts, stopping after `gap_limit` consecutive unused addresses.
+/// Returns the keys of the used accounts.
+///
+/// # Arguments
+///
+/// * `seed` - A seed as bytes array
+/// * `path_template` - A derivation path ending with `{i}` or `{i}'` (e.g "m/44'/461'/0'/0/{i}")
+/// * `gap_limit` - Number of consecutive unused addresses before stopping
+/// * `is_used` - Returns `true` if the address has been used
+pub fn key_scan_gap_limit_from_seed<F>(
+    seed: &[u8],
+    path_template: &str,
+    gap_limit: u32,
+    mut is_used: F,
+) -> Result<Vec<ExtendedKey>, SignerError>
+where
+    F: FnMut(&str) -> bool,
+{
+    if gap_limit == 0 {
+        return Err(SignerError::GenericString(
+            "Gap limit should be greater than 0".to_string(),
+        ));
+    }
+
+    let template = PathTemplate::from_string(path_template)?;
+    let parent = derive_template_parent(seed, &template)?;
+
+    let mut used_keys = Vec::new();
+    let mut unused_count = 0;
+    let mut start = 0u32;
+
+    while unused_count < gap_limit {
+        for key in derive_batch(&parent, &template, start, gap_limit)? {
+            if is_used(&key.address) {
+                used_keys.push(key);
+                unused_count = 0;
+            } else {
+                unused_count += 1;
+                if unused_count == gap_limit {
+                    break;
+                }
+            }
+        }
+
+        start = start
+            .checked_add(gap_limit)
+            .ok_or_else(|| SignerError::GenericString("Index out of range".to_string()))?;
+    }
+
+    Ok(used_keys)
+}
+
+/// Returns the extended public key (`xpub`) at a derivation path (e.g "m/44'/461'/0'")
+///
+/// # Arguments
+///
+/// * `mnemonic` - A string containing a BIP39 mnemonic in the language of `language_code`
+/// * `path` - A string containing a derivation path
+/// * `password` - Password to decrypt seed, if none use and empty string (e.g "")
+/// * `language_code` - The mnemonic language (e.g "en")
+pub fn key_derive_xpub(
+    mnemonic: &str,
+    path: &str,
+    password: &str,
+    language_code: &str,
+) -> Result<String, SignerError> {
+    let seed = seed_from_mnemonic(mnemonic, password, language_code)?;
+    let master = ExtendedSecretKey::try_from(seed.as_bytes())?;
+
+    let indexes = extended_key::parse_derivation_path(path)?;
+    let esk = if indexes.is_empty() {
+        master
+    } else {
+        master.derive_path(&indexes)?
+    };
+
+    Ok(esk.extended_public_key().to_xpub())
+}
+
+/// Returns the address derived from an extended public key without access to any secret
+///
+/// # Arguments
+///
+/// * `xpub` - An extended public key
+/// * `path` - A relative path of non-hardened indexes (e.g "0/5")
+/// * `testnet` - specify the network, `true` if testnet else `false` for mainnet
+///
+pub fn address_derive_from_xpub(
+    xpub: &str,
+    path: &str,
+    testnet: bool,
+) -> Result<String, SignerError> {
+    let epk = ExtendedPublicKey::from_xpub(xpub)?;
+    let child = epk.derive_path(&extended_key::parse_public_path(path)?)?;
+
+    let mut address = Address::new_secp256k1(&child.public_key())?;
+
+    if testnet {
+        address.set_network(Network::Testnet);
+    } else {
+        address.set_network(Network::Mainnet);
+    }
+
+    Ok(address.to_string())
+}
+
+/// Returns a BLS public key, private key and address given a mnemonic, derivation path and a password
+///
+/// The key is derived following EIP-2333 so the same mnemonic can back up both secp256k1 and BLS accounts.
+///
+/// # Arguments
+///
+/// * `mnemonic` - A string containing a BIP39 mnemonic in the language of `language_code`
+/// * `path` - A string containing an EIP-2334 derivation path (e.g "m/12381/461/0/0")
+/// * `password` - Password to decrypt seed, if none use and empty string (e.g "")
+/// * `language_code` - The mnemonic language (e.g "en")
+pub fn key_derive_bls(
+    mnemonic: &str,
+    path: &str,
+    password: &str,
+    language_code: &str,
+) -> Result<ExtendedKey, SignerError> {
+    let seed = seed_from_mnemonic(mnemonic, password, language_code)?;
+
+    key_derive_bls_from_seed(seed.as_bytes(), path)
+}
+
+/// Returns a BLS public key, private key and address given a seed and derivation path
+///
+/// # Arguments
+///
+/// * `seed` - A seed as bytes array (e.g the seed of a BIP39 mnemonic)
+/// * `path` - A string containing an EIP-2334 derivation path (e.g "m/12381/461/0/0")
+///
+pub fn key_derive_bls_from_seed(seed: &[u8], path: &str) -> Result<ExtendedKey, SignerError> {
+    let master = BLSSecretKey::try_from(seed)?;
+    let bls_path = BLSPath::from_string(path)?;
+    let sk = master.derive_path(&bls_path)?;
+
+    key_recover_bls(&PrivateKey(sk.secret_key()), bls_path.is_testnet())
+}
+
 /// Get extended key from private key
 ///
 /// # Arguments
@@ -267,6 +709,54 @@ pub fn key_recover_bls(
     })
 }
 
+/// Generate a random BLS private key and return the extended key
+///
+/// # Arguments
+///
+/// * `testnet` - specify the network, `true` if testnet else `false` for mainnet
+///
+pub fn key_generate_bls(testnet: bool) -> Result<ExtendedKey, SignerError> {
+    // IKM must be at least 32 bytes long
+    let mut ikm = [0u8; 32];
+    getrandom::getrandom(&mut ikm)?;
+
+    let sk = bls_signatures::PrivateKey::new(&ikm);
+    ikm.zeroize();
+
+    let private_key = PrivateKey::try_from(sk.as_bytes())?;
+
+    key_recover_bls(&private_key, testnet)
+}
+
+/// Import a key exported with `lotus wallet export` (hex encoded JSON key info)
+///
+/// # Arguments
+///
+/// * `exported_key` - The hex string returned by `lotus wallet export`
+/// * `testnet` - specify the network, `true` if testnet else `false` for mainnet
+///
+pub fn key_import_lotus(exported_key: &str, testnet: bool) -> Result<ExtendedKey, SignerError> {
+    let mut key_info_json = hex::decode(exported_key.trim())?;
+    let key_info: Result<LotusKeyInfo, _> = serde_json::from_slice(&key_info_json);
+    key_info_json.zeroize();
+
+    key_info?.to_extended_key(testnet)
+}
+
+/// Export a key in the format accepted by `lotus wallet import` (hex encoded JSON key info)
+///
+/// # Arguments
+///
+/// * `extended_key` - The key to export
+///
+pub fn key_export_lotus(extended_key: &ExtendedKey) -> Result<String, SignerError> {
+    let mut key_info_json = serde_json::to_vec(&LotusKeyInfo::from(extended_key))?;
+    let exported_key = hex::encode(&key_info_json);
+    key_info_json.zeroize();
+
+    Ok(exported_key)
+}
+
 /// Serialize a transaction and return a CBOR hexstring.
 ///
 /// # Arguments
@@ -276,7 +766,7 @@ pub fn key_recover_bls(
 pub fn transaction_serialize(
     unsigned_message_arg: &UnsignedMessageAPI,
 ) -> Result<CborBuffer, SignerError> {
-    let unsigned_message = UnsignedMessage::try_from(unsigned_message_arg)?;
+    let unsigned_message = AnyUnsignedMessage::try_from(unsigned_message_arg)?;
     let message_cbor = CborBuffer(to_vec(&unsigned_message)?);
     Ok(message_cbor)
 }
@@ -292,51 +782,102 @@ pub fn transaction_parse(
     cbor_buffer: &CborBuffer,
     testnet: bool,
 ) -> Result<MessageTxAPI, SignerError> {
-    let message: MessageTx = from_slice(cbor_buffer.as_ref())?;
+    let mut message: AnyMessageTx = from_slice(cbor_buffer.as_ref())?;
 
-    let message_tx_with_network = MessageTxNetwork {
-        message_tx: message,
-        testnet,
-    };
+    if testnet {
+        message.set_network(Network::Testnet);
+    } else {
+        message.set_network(Network::Mainnet);
+    }
 
-    let parsed_message = MessageTxAPI::try_from(message_tx_with_network)?;
+    let parsed_message = MessageTxAPI::try_from(message)?;
 
     Ok(parsed_message)
 }
 
-fn transaction_sign_secp56k1_raw(
-    unsigned_message_api: &UnsignedMessageAPI,
-    private_key: &PrivateKey,
-) -> Result<SignatureSECP256K1, SignerError> {
-    let message_cbor = transaction_serialize(unsigned_message_api)?;
-
-    let secret_key = secp256k1::SecretKey::parse_slice(&private_key.0)?;
-
-    let cid_hashed = utils::get_digest(message_cbor.as_ref())?;
+/// Serialize a signed message and return its CBOR (a filecoin `SignedMessage`).
+///
+/// # Arguments
+///
+/// * `signed_message` - a signed filecoin message
+///
+pub fn signed_message_serialize(
+    signed_message: &SignedMessageAPI,
+) -> Result<CborBuffer, SignerError> {
+    let signed_message = AnySignedMessage::try_from(signed_message)?;
+    let message_cbor = CborBuffer(to_vec(&signed_message)?);
+    Ok(message_cbor)
+}
 
-    let message_digest = Message::parse_slice(&cid_hashed)?;
+/// Parse the CBOR of a signed message. The signature type is preserved.
+///
+/// # Arguments
+///
+/// * `cbor_buffer` - the CBOR of a filecoin `SignedMessage`
+/// * `testnet` - boolean value `true` if testnet or `false` for mainnet
+///
+pub fn signed_message_parse(
+    cbor_buffer: &CborBuffer,
+    testnet: bool,
+) -> Result<SignedMessageAPI, SignerError> {
+    let mut signed_message: AnySignedMessage = from_slice(cbor_buffer.as_ref())?;
 
-    let (signature_rs, recovery_id) = sign(&message_digest, &secret_key);
+    if testnet {
+        signed_message.message.set_network(Network::Testnet);
+    } else {
+        signed_message.message.set_network(Network::Mainnet);
+    }
 
-    let mut signature = SignatureSECP256K1 { 0: [0; 65] };
-    signature.0[..64].copy_from_slice(&signature_rs.serialize()[..]);
-    signature.0[64] = recovery_id.serialize();
+    SignedMessageAPI::try_from(signed_message)
+}
 
-    Ok(signature)
+fn in_memory_signer(from: &str, private_key: &PrivateKey) -> Result<ExtendedKey, SignerError> {
+    // the `from` address protocol let us know which signing scheme to use
+    match from
+        .as_bytes()
+        .get(1)
+        .ok_or_else(|| SignerError::GenericString("Empty signing protocol".into()))?
+    {
+        b'1' => key_recover(private_key, from.starts_with('t')),
+        b'3' => key_recover_bls(private_key, from.starts_with('t')),
+        _ => Err(SignerError::GenericString(
+            "Unknown signing protocol".to_string(),
+        )),
+    }
 }
 
-fn transaction_sign_bls_raw(
+/// Sign a transaction with a `Signer` and return a raw signature (RSV format for secp256k1).
+///
+/// # Arguments
+///
+/// * `unsigned_message_api` - an unsigned filecoin message
+/// * `signer` - a `Signer` matching the `from` address protocol
+///
+pub fn transaction_sign_raw_with_signer<S: Signer + ?Sized>(
     unsigned_message_api: &UnsignedMessageAPI,
-    private_key: &PrivateKey,
-) -> Result<SignatureBLS, SignerError> {
-    let sk = bls_signatures::PrivateKey::from_bytes(&private_key.0)?;
+    signer: &S,
+) -> Result<Signature, SignerError> {
+    let from = AnyAddress::from_str(&unsigned_message_api.from)?;
+    let signer_address = AnyAddress::from_str(signer.address())?;
+
+    // The network of the addresses doesn't matter
+    if from.protocol() != signer_address.protocol()
+        || from.payload_bytes() != signer_address.payload_bytes()
+    {
+        return Err(SignerError::GenericString(
+            "Signer address doesn't match the `from` address".to_string(),
+        ));
+    }
 
-    let unsigned_message = UnsignedMessage::try_from(unsigned_message_api)?;
+    if from.protocol() != signer.key_type().address_protocol() as u8 {
+        return Err(SignerError::GenericString(
+            "Signer key type doesn't match the `from` address protocol".to_string(),
+        ));
+    }
 
-    //sign the message's signing bytes
-    let sig = sk.sign(unsigned_message.to_signing_bytes());
+    let unsigned_message = AnyUnsignedMessage::try_from(unsigned_message_api)?;
 
-    Ok(SignatureBLS::try_from(sig.as_bytes())?)
+    signer.sign_bytes(&unsigned_message.to_signing_bytes()?)
 }
 
 /// Sign a transaction and return a raw signature (RSV format).
@@ -350,28 +891,30 @@ pub fn transaction_sign_raw(
     unsigned_message_api: &UnsignedMessageAPI,
     private_key: &PrivateKey,
 ) -> Result<Signature, SignerError> {
-    // the `from` address protocol let us know which signing scheme to use
-    let signature = match unsigned_message_api
-        .from
-        .as_bytes()
-        .get(1)
-        .ok_or_else(|| SignerError::GenericString("Empty signing protocol".into()))?
-    {
-        b'1' => Signature::SignatureSECP256K1(transaction_sign_secp56k1_raw(
-            unsigned_message_api,
-            private_key,
-        )?),
-        b'3' => {
-            Signature::SignatureBLS(transaction_sign_bls_raw(unsigned_message_api, private_key)?)
-        }
-        _ => {
-            return Err(SignerError::GenericString(
-                "Unknown signing protocol".to_string(),
-            ));
-        }
+    let signer = in_memory_signer(&unsigned_message_api.from, private_key)?;
+
+    transaction_sign_raw_with_signer(unsigned_message_api, &signer)
+}
+
+/// Sign a transaction with a `Signer` and return a signed message (message + signature).
+///
+/// # Arguments
+///
+/// * `unsigned_message_api` - an unsigned filecoin message
+/// * `signer` - a `Signer` matching the `from` address protocol
+///
+pub fn transaction_sign_with_signer<S: Signer + ?Sized>(
+    unsigned_message: &UnsignedMessageAPI,
+    signer: &S,
+) -> Result<SignedMessageAPI, SignerError> {
+    let signature = transaction_sign_raw_with_signer(unsigned_message, signer)?;
+
+    let signed_message = SignedMessageAPI {
+        message: unsigned_message.to_owned(),
+        signature: SignatureAPI::from(&signature),
     };
 
-    Ok(signature)
+    Ok(signed_message)
 }
 
 /// Sign a transaction and return a signed message (message + signature).
@@ -385,117 +928,387 @@ pub fn transaction_sign(
     unsigned_message: &UnsignedMessageAPI,
     private_key: &PrivateKey,
 ) -> Result<SignedMessageAPI, SignerError> {
-    let signature = transaction_sign_raw(unsigned_message, private_key)?;
+    let signer = in_memory_signer(&unsigned_message.from, private_key)?;
 
-    let signed_message = SignedMessageAPI {
-        message: unsigned_message.to_owned(),
-        signature: SignatureAPI::from(&signature),
+    transaction_sign_with_signer(unsigned_message, &signer)
+}
+
+/// Sign a transaction in strict mode: the message is checked with `validation::validate_message`
+/// first and isn't signed if any error is found (warnings are allowed).
+///
+/// # Arguments
+///
+/// * `unsigned_message_api` - an unsigned filecoin message
+/// * `private_key` - a `PrivateKey`
+///
+pub fn transaction_sign_strict(
+    unsigned_message: &UnsignedMessageAPI,
+    private_key: &PrivateKey,
+) -> Result<SignedMessageAPI, SignerError> {
+    let report = validate_message(unsigned_message);
+    if !report.is_valid() {
+        return Err(SignerError::InvalidMessage(report));
+    }
+
+    transaction_sign(unsigned_message, private_key)
+}
+
+pub(crate) fn recover_secp256k1_public_key(
+    signature: &SignatureSECP256K1,
+    digest: &[u8; 32],
+) -> Result<secp256k1::PublicKey, SignerError> {
+    let signature_rs = secp256k1::Signature::parse_slice(&signature.0[..64])?;
+    let recovery_id = RecoveryId::parse(signature.0[64])?;
+
+    let message_digest = Message::parse(digest);
+
+    Ok(recover(&message_digest, &signature_rs, &recovery_id)?)
+}
+
+/// Recover the public key and the address that produced a secp256k1 signature
+///
+/// # Arguments
+///
+/// * `signature` - RSV format signature
+/// * `payload` - the signed payload (CBOR transaction, voucher or digest)
+/// * `testnet` - specify the network of the returned address, `true` if testnet else `false` for mainnet
+///
+pub fn recover_signer(
+    signature: &SignatureSECP256K1,
+    payload: SignedPayload,
+    testnet: bool,
+) -> Result<RecoveredSigner, SignerError> {
+    let digest = match payload {
+        SignedPayload::Transaction(cbor_buffer) => utils::get_digest(cbor_buffer.as_ref())?,
+        SignedPayload::Voucher(voucher) => {
+            let voucher: paych::SignedVoucher = from_slice(voucher)?;
+            utils::get_digest_voucher(&voucher.signing_bytes()?)?
+        }
+        SignedPayload::Digest(digest) => *digest,
     };
 
-    Ok(signed_message)
+    let public_key = recover_secp256k1_public_key(signature, &digest)?.serialize();
+
+    let mut address = Address::new_secp256k1(&public_key)?;
+    if testnet {
+        address.set_network(Network::Testnet);
+    } else {
+        address.set_network(Network::Mainnet);
+    }
+
+    Ok(RecoveredSigner {
+        public_key: PublicKeySECP256K1(public_key),
+        address: address.to_string(),
+    })
+}
+
+/// Sign a batch of transactions with a `Signer` in parallel. Return a result per transaction, in
+/// the same order as the transactions.
+///
+/// # Arguments
+///
+/// * `unsigned_messages` - the unsigned filecoin messages
+/// * `signer` - a `Signer` matching the `from` address protocol of the messages
+///
+pub fn transaction_sign_batch_with_signer<S: Signer + Sync + ?Sized>(
+    unsigned_messages: &[UnsignedMessageAPI],
+    signer: &S,
+) -> Vec<Result<SignedMessageAPI, SignerError>> {
+    unsigned_messages
+        .par_iter()
+        .map(|unsigned_message| transaction_sign_with_signer(unsigned_message, signer))
+        .collect()
+}
+
+/// Sign a batch of transactions in parallel. Return a result per transaction, in the same order as
+/// the transactions.
+///
+/// # Arguments
+///
+/// * `unsigned_messages` - the unsigned filecoin messages
+/// * `private_key` - a `PrivateKey`
+///
+pub fn transaction_sign_batch(
+    unsigned_messages: &[UnsignedMessageAPI],
+    private_key: &PrivateKey,
+) -> Vec<Result<SignedMessageAPI, SignerError>> {
+    unsigned_messages
+        .par_iter()
+        .map(|unsigned_message| transaction_sign(unsigned_message, private_key))
+        .collect()
+}
+
+fn unsigned_message_from_cbor(cbor_buffer: &CborBuffer) -> Result<AnyUnsignedMessage, SignerError> {
+    let message: AnyMessageTx = from_slice(cbor_buffer.as_ref())?;
+
+    match message {
+        AnyMessageTx::UnsignedMessage(message) => Ok(message),
+        AnyMessageTx::SignedMessage(message) => Ok(message.message),
+    }
 }
 
 fn verify_secp256k1_signature(
     signature: &SignatureSECP256K1,
     cbor_buffer: &CborBuffer,
 ) -> Result<bool, SignerError> {
-    let network = Network::Testnet;
+    let message = unsigned_message_from_cbor(cbor_buffer)?;
+
+    let message_digest = utils::get_digest(cbor_buffer.as_ref())?;
+
+    let public_key = recover_secp256k1_public_key(signature, &message_digest)?;
+    let from = Address::new_secp256k1(&public_key.serialize().to_vec())?;
+
+    // Compare recovered public key with the public key from the transaction
+    // (the network of the `from` address doesn't matter)
+    if !is_same_any_address(&from, &message.from) {
+        return Ok(false);
+    }
 
     let signature_rs = secp256k1::Signature::parse_slice(&signature.0[..64])?;
-    let recovery_id = RecoveryId::parse(signature.0[64])?;
 
-    // Should be default network here
-    // FIXME: For now only testnet
-    let tx = transaction_parse(cbor_buffer, network == Network::Testnet)?;
+    Ok(verify(
+        &Message::parse(&message_digest),
+        &signature_rs,
+        &public_key,
+    ))
+}
 
-    // Decode the CBOR transaction hex string into CBOR transaction buffer
-    let message_digest = utils::get_digest(cbor_buffer.as_ref())?;
+fn verify_bls_signature(
+    signature: &SignatureBLS,
+    cbor_buffer: &CborBuffer,
+) -> Result<bool, SignerError> {
+    let message = unsigned_message_from_cbor(cbor_buffer)?;
+
+    if message.from.protocol() != Protocol::BLS as u8 {
+        return Ok(false);
+    }
+
+    let pk = bls_signatures::PublicKey::from_bytes(&message.from.payload_bytes())?;
+
+    let sig = bls_signatures::Signature::from_bytes(signature.as_ref())?;
+
+    let signing_bytes = message.to_signing_bytes()?;
+
+    let result = pk.verify(sig, signing_bytes);
+
+    Ok(result)
+}
+
+/// Verify a signature. Return a boolean.
+///
+/// # Arguments
+///
+/// * `signature` - RSV format signature or BLS signature
+/// * `cbor_buffer` - the CBOR transaction to verify the signature against
+///
+pub fn verify_signature(
+    signature: &Signature,
+    cbor_buffer: &CborBuffer,
+) -> Result<bool, SignerError> {
+    let result = match signature {
+        Signature::SignatureSECP256K1(sig_secp256k1) => {
+            verify_secp256k1_signature(sig_secp256k1, cbor_buffer)?
+        }
+        Signature::SignatureBLS(sig_bls) => verify_bls_signature(sig_bls, cbor_buffer)?,
+        Signature::SignatureDelegated(_) => {
+            return Err(SignerError::GenericString(
+                "Delegated signatures sign an Ethereum transaction, use `eth::verify_eth_signed_message`"
+                    .to_string(),
+            ))
+        }
+    };
 
-    let blob_to_sign = Message::parse_slice(&message_digest)?;
+    Ok(result)
+}
 
-    let public_key = recover(&blob_to_sign, &signature_rs, &recovery_id)?;
-    let mut from = Address::new_secp256k1(&public_key.serialize().to_vec())?;
-    from.set_network(network);
+/// Verify a signed message. The signature type must match the `from` address protocol and the
+/// signature must be valid for the message. Return a boolean.
+///
+/// # Arguments
+///
+/// * `signed_message` - the signed message to verify
+///
+pub fn verify_signed_message(signed_message: &SignedMessageAPI) -> Result<bool, SignerError> {
+    if signed_message.signature.sig_type == SigTypes::SigTypeDelegated as u8 {
+        return Err(SignerError::GenericString(
+            "Delegated signatures sign an Ethereum transaction, use `eth::verify_eth_signed_message`"
+                .to_string(),
+        ));
+    }
 
-    let tx_from = match tx {
-        MessageTxAPI::UnsignedMessageAPI(tx) => tx.from,
-        MessageTxAPI::SignedMessageAPI(tx) => tx.message.from,
+    let from = Address::from_str(&signed_message.message.from)?;
+    let signature_data = signed_message.signature.data.clone();
+
+    let (protocol, signature) = match signed_message.signature.sig_type {
+        sig_type if sig_type == SigTypes::SigTypeSecp256k1 as u8 => (
+            Protocol::Secp256k1,
+            Signature::SignatureSECP256K1(SignatureSECP256K1::try_from(signature_data)?),
+        ),
+        sig_type if sig_type == SigTypes::SigTypeBLS as u8 => (
+            Protocol::BLS,
+            Signature::SignatureBLS(SignatureBLS::try_from(signature_data)?),
+        ),
+        sig_type => {
+            return Err(SignerError::GenericString(format!(
+                "Unknown signature type {}",
+                sig_type
+            )))
+        }
     };
-    let expected_from = from.to_string();
 
-    // Compare recovered public key with the public key from the transaction
-    if tx_from != expected_from {
+    if from.protocol() != protocol {
         return Ok(false);
     }
 
-    Ok(verify(&blob_to_sign, &signature_rs, &public_key))
-}
+    let cbor_buffer = transaction_serialize(&signed_message.message)?;
 
-fn verify_bls_signature(
-    signature: &SignatureBLS,
-    cbor_buffer: &CborBuffer,
-) -> Result<bool, SignerError> {
-    // TODO: need a function to extract from public key from cbor buffer directly
-    let message = transaction_parse(cbor_buffer, true)?;
-    let message = message.get_message();
+    verify_signature(&signature, &cbor_buffer)
+}
 
-    let address = Address::from_str(&message.from)?;
+/// Verify a batch of signatures in parallel. Return a result per signature, in the same order as
+/// the signatures.
+///
+/// # Arguments
+///
+/// * `signatures` - pairs of signature and CBOR transaction to verify the signature against
+///
+pub fn verify_signature_batch(
+    signatures: &[(Signature, CborBuffer)],
+) -> Vec<Result<bool, SignerError>> {
+    signatures
+        .par_iter()
+        .map(|(signature, cbor_buffer)| verify_signature(signature, cbor_buffer))
+        .collect()
+}
 
-    let pk = bls_signatures::PublicKey::from_bytes(&address.payload_bytes())?;
+/// Verify a batch of signed messages in parallel. Return a result per message, in the same order as
+/// the messages.
+///
+/// # Arguments
+///
+/// * `signed_messages` - the signed messages to verify
+///
+pub fn verify_signed_message_batch(
+    signed_messages: &[SignedMessageAPI],
+) -> Vec<Result<bool, SignerError>> {
+    signed_messages
+        .par_iter()
+        .map(verify_signed_message)
+        .collect()
+}
 
-    let sig = bls_signatures::Signature::from_bytes(signature.as_ref())?;
+fn message_signing_bytes(message: &[u8], prefix: Option<&[u8]>) -> Vec<u8> {
+    match prefix {
+        Some(prefix) => [prefix, message].concat(),
+        None => message.to_vec(),
+    }
+}
 
-    let message = UnsignedMessage::try_from(&message)?;
-    let signing_bytes = message.to_signing_bytes();
+/// Compare two addresses regardless of their network
+fn is_same_address(address: &Address, other: &Address) -> bool {
+    address.protocol() == other.protocol() && address.payload_bytes() == other.payload_bytes()
+}
 
-    let result = pk.verify(sig, signing_bytes);
+fn is_same_any_address(address: &Address, other: &AnyAddress) -> bool {
+    other
+        .as_address()
+        .map_or(false, |other| is_same_address(address, other))
+}
 
-    Ok(result)
+/// Sign arbitrary bytes (e.g login challenge). Without prefix the signature is compatible
+/// with Lotus `WalletSign` raw signing. The prefix is prepended as is: callers following a wallet
+/// convention supply the full prefix (e.g FilSnap appends the payload length to
+/// `"\x19Filecoin Signed Message:\n"`).
+///
+/// # Arguments
+///
+/// * `message` - the bytes to sign
+/// * `prefix` - optional domain separation prefix prepended to the message
+/// * `signer` - a `Signer`
+///
+pub fn sign_message<S: Signer + ?Sized>(
+    message: &[u8],
+    prefix: Option<&[u8]>,
+    signer: &S,
+) -> Result<Signature, SignerError> {
+    signer.sign_bytes(&message_signing_bytes(message, prefix))
 }
 
-/// Verify a signature. Return a boolean.
+/// Verify a signature over arbitrary bytes for an address (mainnet or testnet). Return a boolean.
 ///
 /// # Arguments
 ///
 /// * `signature` - RSV format signature or BLS signature
-/// * `cbor_buffer` - the CBOR transaction to verify the signature against
+/// * `message` - the signed bytes
+/// * `prefix` - the domain separation prefix used when signing, if any
+/// * `address` - the address expected to have signed the message
 ///
-pub fn verify_signature(
+pub fn verify_message(
     signature: &Signature,
-    cbor_buffer: &CborBuffer,
+    message: &[u8],
+    prefix: Option<&[u8]>,
+    address: &str,
 ) -> Result<bool, SignerError> {
-    let result = match signature {
+    let address = Address::from_str(address)?;
+    let signing_bytes = message_signing_bytes(message, prefix);
+
+    match signature {
         Signature::SignatureSECP256K1(sig_secp256k1) => {
-            verify_secp256k1_signature(sig_secp256k1, cbor_buffer)?
-        }
-        Signature::SignatureBLS(sig_bls) => verify_bls_signature(sig_bls, cbor_buffer)?,
-    };
+            if address.protocol() != Protocol::Secp256k1 {
+                return Ok(false);
+            }
 
-    Ok(result)
-}
+            let digest = blake2b_256(&signing_bytes);
+
+            let public_key = recover_secp256k1_public_key(sig_secp256k1, &digest)?;
+            let signer_address = Address::new_secp256k1(&public_key.serialize())?;
 
-fn extract_from_pub_key_from_message(
-    cbor_message: &CborBuffer,
-) -> Result<bls_signatures::PublicKey, SignerError> {
-    let message = transaction_parse(cbor_message, true)?;
+            let signature_rs = secp256k1::Signature::parse_slice(&sig_secp256k1.0[..64])?;
 
-    let unsigned_message_api = message.get_message();
-    let from_address = Address::from_str(&unsigned_message_api.from)?;
+            Ok(is_same_address(&signer_address, &address)
+                && verify(&Message::parse(&digest), &signature_rs, &public_key))
+        }
+        Signature::SignatureBLS(sig_bls) => {
+            if address.protocol() != Protocol::BLS {
+                return Ok(false);
+            }
 
-    let pk = bls_signatures::PublicKey::from_bytes(&from_address.payload_bytes())?;
+            let pk = bls_signatures::PublicKey::from_bytes(&address.payload_bytes())?;
+            let sig = bls_signatures::Signature::from_bytes(sig_bls.as_ref())?;
 
-    Ok(pk)
+            Ok(pk.verify(sig, &signing_bytes))
+        }
+        Signature::SignatureDelegated(_) => Err(SignerError::GenericString(
+            "Delegated signatures can't sign arbitrary messages".to_string(),
+        )),
+    }
 }
 
-fn extract_bls_signing_bytes_from_message(
-    cbor_message: &CborBuffer,
-) -> Result<Vec<u8>, SignerError> {
-    let message = transaction_parse(cbor_message, true)?;
+/// Aggregate BLS signatures into a single signature. Signatures can be over distinct messages.
+///
+/// Aggregation is incremental: a previously aggregated signature can be given as input with new
+/// signatures to add them to the aggregate.
+///
+/// # Arguments
+///
+/// * `signatures` - the BLS signatures (or aggregated signatures) to aggregate
+///
+pub fn aggregate_signatures(signatures: &[SignatureBLS]) -> Result<SignatureBLS, SignerError> {
+    if signatures.is_empty() {
+        return Err(SignerError::GenericString(
+            "No signature to aggregate".to_string(),
+        ));
+    }
+
+    // Decoding checks that every signature is a valid point of the G2 subgroup
+    let sigs = signatures
+        .par_iter()
+        .map(|signature| bls_signatures::Signature::from_bytes(signature.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let unsigned_message_api = message.get_message();
-    let unsigned_message = UnsignedMessage::try_from(&unsigned_message_api)?;
+    let aggregated_signature = bls_signatures::aggregate(&sigs)?;
 
-    Ok(unsigned_message.to_signing_bytes())
+    SignatureBLS::try_from(aggregated_signature.as_bytes())
 }
 
 pub fn verify_aggregated_signature(
@@ -504,60 +1317,100 @@ pub fn verify_aggregated_signature(
 ) -> Result<bool, SignerError> {
     let sig = bls_signatures::Signature::from_bytes(signature.as_ref())?;
 
-    // Get public keys from message
+    // Parse every message once
     let tmp: Result<Vec<_>, SignerError> = cbor_messages
-        .iter()
-        .map(|cbor_message| extract_from_pub_key_from_message(cbor_message))
+        .par_iter()
+        .map(unsigned_message_from_cbor)
         .collect();
 
-    let pks = match tmp {
-        Ok(public_keys) => public_keys,
+    let messages = match tmp {
+        Ok(messages) => messages,
         Err(_) => {
             return Err(SignerError::GenericString(
-                "Invalid public key extracted from message".to_string(),
+                "An invalid message was provided".to_string(),
             ));
         }
     };
 
-    // Hashes
-    let tmp: Result<Vec<_>, SignerError> = cbor_messages
+    // Only BLS senders can take part in an aggregated signature
+    if messages
+        .iter()
+        .any(|message| message.from.protocol() != Protocol::BLS as u8)
+    {
+        return Ok(false);
+    }
+
+    // Get public keys from message
+    let tmp: Result<Vec<_>, _> = messages
         .iter()
-        .map(|cbor_message| extract_bls_signing_bytes_from_message(cbor_message))
+        .map(|message| bls_signatures::PublicKey::from_bytes(&message.from.payload_bytes()))
         .collect();
 
-    let signing_bytes = match tmp {
-        Ok(bytes) => bytes,
+    let pks = match tmp {
+        Ok(public_keys) => public_keys,
         Err(_) => {
             return Err(SignerError::GenericString(
-                "An invalid message was provided".to_string(),
+                "Invalid public key extracted from message".to_string(),
             ));
         }
     };
 
+    // Hashes
+    let signing_bytes = messages
+        .iter()
+        .map(|message| message.to_signing_bytes())
+        .collect::<Result<Vec<_>, SignerError>>()?;
+
     let hashes = signing_bytes
         .par_iter()
-        .map(|signing_bytes| bls_signatures::hash(signing_bytes.as_ref()))
+        .map(|bytes| bls_signatures::hash(bytes))
         .collect::<Vec<_>>();
 
     Ok(bls_signatures::verify(&sig, &hashes, pks.as_slice()))
 }
 
+/// Unsigned message calling an actor method, with the arguments of the message helpers kept as
+/// they are. Unlike `MessageBuilder::build`, the message isn't validated.
+#[allow(clippy::too_many_arguments)]
+fn call_message<P: MessageParamsProvider + ?Sized>(
+    call: &P,
+    to: String,
+    from: String,
+    nonce: u64,
+    value: String,
+    gas_limit: i64,
+    gas_fee_cap: String,
+    gas_premium: String,
+) -> Result<UnsignedMessageAPI, SignerError> {
+    Ok(UnsignedMessageAPI {
+        to,
+        from,
+        nonce,
+        value,
+        gas_limit,
+        gas_fee_cap,
+        gas_premium,
+        method: call.method(),
+        params: base64::encode(call.params()?.bytes()),
+    })
+}
+
 /// Utilitary function to create a create multisig message. Return an unsigned message.
 ///
 /// # Arguments
 ///
 /// * `sender_address` - A string address
 /// * `addresses` - List of string addresses of the multisig
-/// * `value` - Value to send on the multisig
+/// * `value` - Value to send on the multisig (`TokenAmount` or attoFIL string)
 /// * `required` - Number of required signatures required
 /// * `nonce` - Nonce of the message
 /// * `duration` - Duration of the multisig
 ///
 #[allow(clippy::too_many_arguments)]
-pub fn create_multisig(
+pub fn create_multisig<V: IntoTokenAmount>(
     sender_address: String,
     addresses: Vec<String>,
-    value: String,
+    value: V,
     required: i64,
     nonce: u64,
     duration: i64,
@@ -580,45 +1433,21 @@ pub fn create_multisig(
         }
     };
 
-    if duration < 0 && duration != -1 {
-        return Err(SignerError::GenericString(
-            "Invalid duration value (duration >= -1)".to_string(),
-        ));
-    };
-
-    let constructor_params_multisig = multisig::ConstructorParams {
-        signers,
-        num_approvals_threshold: required,
-        unlock_duration: duration,
-        start_epoch,
-    };
-
-    let serialized_constructor_params = forest_vm::Serialized::serialize::<
-        multisig::ConstructorParams,
-    >(constructor_params_multisig)
-    .map_err(|err| SignerError::GenericString(err.to_string()))?;
-
-    let message_params_multisig = ExecParams {
-        code_cid: Cid::new_v1(forest_cid::RAW, Identity.digest(b"fil/5/multisig")),
-        constructor_params: serialized_constructor_params,
-    };
-
-    let serialized_params = forest_vm::Serialized::serialize::<ExecParams>(message_params_multisig)
-        .map_err(|err| SignerError::GenericString(err.to_string()))?;
-
-    let multisig_create_message_api = UnsignedMessageAPI {
-        to: INIT_ACTOR_ADDR.to_string(),
-        from: sender_address,
+    call_message(
+        &builder::CreateMultisig {
+            signers,
+            num_approvals_threshold: required,
+            unlock_duration: duration,
+            start_epoch,
+        },
+        INIT_ACTOR_ADDR.to_string(),
+        sender_address,
         nonce,
-        value,
+        value.into_token_amount()?.to_atto_string(),
         gas_limit,
         gas_fee_cap,
         gas_premium,
-        method: MethodInit::Exec as u64,
-        params: base64::encode(serialized_params.bytes()),
-    };
-
-    Ok(multisig_create_message_api)
+    )
 }
 
 /// Utilitary function to create a proposal multisig message. Return an unsigned message.
@@ -628,7 +1457,7 @@ pub fn create_multisig(
 /// * `multisig_address` - A string address
 /// * `to_address` - A string address
 /// * `from_address` - A string address
-/// * `amount` - Amount of the transaction
+/// * `amount` - Amount of the transaction (`TokenAmount` or attoFIL string)
 /// * `nonce` - Nonce of the message
 /// * `gas_limit` - The gas limit
 /// * `gas_fee_cap` - The gas fee cap
@@ -637,11 +1466,11 @@ pub fn create_multisig(
 /// * `proposal_serialized_params` - The proposal parameters serialized
 ///
 #[allow(clippy::too_many_arguments)]
-pub fn proposal_multisig_message(
+pub fn proposal_multisig_message<A: IntoTokenAmount>(
     multisig_address: String,
     to_address: String,
     from_address: String,
-    amount: String,
+    amount: A,
     nonce: u64,
     gas_limit: i64,
     gas_fee_cap: String,
@@ -649,35 +1478,26 @@ pub fn proposal_multisig_message(
     proposal_method: u64,
     proposal_serialized_params: String,
 ) -> Result<UnsignedMessageAPI, SignerError> {
-    let propose_params_multisig = multisig::ProposeParams {
-        to: Address::from_str(&to_address)?,
-        value: BigInt::from_str(&amount)?,
-        method: proposal_method,
-        params: forest_vm::Serialized::new(base64::decode(proposal_serialized_params)?),
-    };
-
-    let params =
-        forest_vm::Serialized::serialize::<multisig::ProposeParams>(propose_params_multisig)
-            .map_err(|err| SignerError::GenericString(err.to_string()))?;
-
-    let multisig_propose_message_api = UnsignedMessageAPI {
-        to: multisig_address,
-        from: from_address,
+    call_message(
+        &builder::ProposeMultisig {
+            to: AnyAddress::from_str(&to_address)?,
+            value: amount.into_token_amount()?,
+            method: proposal_method,
+            params: base64::decode(proposal_serialized_params)?,
+        },
+        multisig_address,
+        from_address,
         nonce,
-        value: "0".to_string(),
+        "0".to_string(),
         gas_limit,
         gas_fee_cap,
         gas_premium,
-        method: multisig::MethodMultisig::Propose as u64,
-        params: base64::encode(params.bytes()),
-    };
-
-    Ok(multisig_propose_message_api)
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
 fn approve_or_cancel_multisig_message(
-    method: u64,
+    approve: bool,
     multisig_address: String,
     message_id: i64,
     proposer_address: String,
@@ -689,40 +1509,29 @@ fn approve_or_cancel_multisig_message(
     gas_fee_cap: String,
     gas_premium: String,
 ) -> Result<UnsignedMessageAPI, SignerError> {
-    let proposal_parameter = multisig::ProposalHashData {
+    let transaction = builder::MultisigTransaction {
+        txn_id: message_id,
         requester: Address::from_str(&proposer_address)?,
-        to: Address::from_str(&to_address)?,
-        value: BigInt::from_str(&amount)?,
-        method: 0,
-        params: forest_vm::Serialized::new(Vec::new()),
+        to: AnyAddress::from_str(&to_address)?,
+        value: TokenAmount::from_atto(BigInt::from_str(&amount)?)?,
     };
 
-    let serialize_proposal_parameter =
-        forest_vm::Serialized::serialize::<multisig::ProposalHashData>(proposal_parameter)
-            .map_err(|err| SignerError::GenericString(err.to_string()))?;
-    let proposal_hash = blake2b_256(&serialize_proposal_parameter);
-
-    let params_txnid = multisig::TxnIDParams {
-        id: multisig::TxnID(message_id),
-        proposal_hash: proposal_hash.to_vec(),
+    let call: Box<dyn MessageParamsProvider> = if approve {
+        Box::new(builder::ApproveMultisig(transaction))
+    } else {
+        Box::new(builder::CancelMultisig(transaction))
     };
 
-    let params = forest_vm::Serialized::serialize::<multisig::TxnIDParams>(params_txnid)
-        .map_err(|err| SignerError::GenericString(err.to_string()))?;
-
-    let multisig_unsigned_message_api = UnsignedMessageAPI {
-        to: multisig_address,
-        from: from_address,
+    call_message(
+        call.as_ref(),
+        multisig_address,
+        from_address,
         nonce,
-        value: "0".to_string(),
+        "0".to_string(),
         gas_limit,
         gas_fee_cap,
         gas_premium,
-        method,
-        params: base64::encode(params.bytes()),
-    };
-
-    Ok(multisig_unsigned_message_api)
+    )
 }
 
 /// Utilitary function to create an approve multisig message. Return an unsigned message.
@@ -751,7 +1560,7 @@ pub fn approve_multisig_message(
     gas_premium: String,
 ) -> Result<UnsignedMessageAPI, SignerError> {
     approve_or_cancel_multisig_message(
-        multisig::MethodMultisig::Approve as u64,
+        true,
         multisig_address,
         message_id,
         proposer_address,
@@ -791,7 +1600,7 @@ pub fn cancel_multisig_message(
     gas_premium: String,
 ) -> Result<UnsignedMessageAPI, SignerError> {
     approve_or_cancel_multisig_message(
-        multisig::MethodMultisig::Cancel as u64,
+        false,
         multisig_address,
         message_id,
         proposer_address,
@@ -823,52 +1632,36 @@ pub fn serialize_params(params: MessageParams) -> Result<CborBuffer, SignerError
 ///
 /// * `from_address` - A string address
 /// * `to_address` - A string address
-/// * `value` - Amount to put in the payment channel initially
+/// * `value` - Amount to put in the payment channel initially (`TokenAmount` or attoFIL string)
 /// * `nonce` - Nonce of the message; should be from_address's MpoolGetNonce() value
 ///
-pub fn create_pymtchan(
+pub fn create_pymtchan<V: IntoTokenAmount>(
     from_address: String,
     to_address: String,
-    value: String,
+    value: V,
     nonce: u64,
     gas_limit: i64,
     gas_fee_cap: String,
     gas_premium: String,
 ) -> Result<UnsignedMessageAPI, SignerError> {
     let from = Address::from_str(&from_address)?;
-    let to = Address::from_str(&to_address)?;
-
-    let create_payment_channel_params = paych::ConstructorParams { from, to };
-
-    let serialized_constructor_params =
-        forest_vm::Serialized::serialize::<paych::ConstructorParams>(create_payment_channel_params)
-            .map_err(|err| SignerError::GenericString(err.to_string()))?;
-
-    let message_params_create_pymtchan = ExecParams {
-        code_cid: Cid::new_v1(forest_cid::RAW, Identity.digest(b"fil/5/paymentchannel")),
-        constructor_params: serialized_constructor_params,
-    };
-
-    let serialized_params =
-        forest_vm::Serialized::serialize::<ExecParams>(message_params_create_pymtchan)
-            .map_err(|err| SignerError::GenericString(err.to_string()))?;
 
-    let mut init_actor_address = Address::from_str("f01")?;
+    let mut init_actor_address = *INIT_ACTOR_ADDR;
     init_actor_address.set_network(from.network());
 
-    let pch_create_message_api = UnsignedMessageAPI {
-        to: init_actor_address.to_string(),
-        from: from_address,
+    call_message(
+        &builder::CreatePaymentChannel {
+            from,
+            to: AnyAddress::from_str(&to_address)?,
+        },
+        init_actor_address.to_string(),
+        from_address,
         nonce,
-        value,
+        value.into_token_amount()?.to_atto_string(),
         gas_limit,
         gas_fee_cap,
         gas_premium,
-        method: MethodInit::Exec as u64,
-        params: base64::encode(serialized_params.bytes()),
-    };
-
-    Ok(pch_create_message_api)
+    )
 }
 
 /// Utility function to update the state of a payment channel.  Returns unsigned message.
@@ -889,31 +1682,18 @@ pub fn update_pymtchan(
     gas_fee_cap: String,
     gas_premium: String,
 ) -> Result<UnsignedMessageAPI, SignerError> {
-    let sv_cbor = base64::decode(signed_voucher)?;
-
-    let sv: paych::SignedVoucher = forest_encoding::from_slice(sv_cbor.as_ref())?;
-
-    let update_payment_channel_params = paych::UpdateChannelStateParams { sv, secret: vec![] };
-
-    let serialized_params = forest_vm::Serialized::serialize::<paych::UpdateChannelStateParams>(
-        update_payment_channel_params,
-    )
-    .map_err(|err| SignerError::GenericString(err.to_string()))?;
-
-    // TODO:  don't hardcode gas limit and gas price; use a gas estimator!
-    let pch_update_message_api = UnsignedMessageAPI {
-        to: pch_address, // INIT_ACTOR_ADDR
-        from: from_address,
+    call_message(
+        &builder::UpdatePaymentChannel {
+            signed_voucher: base64::decode(signed_voucher)?,
+        },
+        pch_address,
+        from_address,
         nonce,
-        value: "0".to_string(),
+        "0".to_string(),
         gas_limit,
         gas_fee_cap,
         gas_premium,
-        method: paych::MethodsPaych::UpdateChannelState as u64,
-        params: base64::encode(serialized_params.bytes()),
-    };
-
-    Ok(pch_update_message_api)
+    )
 }
 
 /// Utility function to generate a payment channel settle message.  Returns unsigned message.
@@ -932,20 +1712,16 @@ pub fn settle_pymtchan(
     gas_fee_cap: String,
     gas_premium: String,
 ) -> Result<UnsignedMessageAPI, SignerError> {
-    // TODO:  don't hardcode gas limit and gas price; use a gas estimator!
-    let pch_settle_message_api = UnsignedMessageAPI {
-        to: pch_address,
-        from: from_address,
+    call_message(
+        &builder::SettlePaymentChannel,
+        pch_address,
+        from_address,
         nonce,
-        value: "0".to_string(),
+        "0".to_string(),
         gas_limit,
         gas_fee_cap,
         gas_premium,
-        method: paych::MethodsPaych::Settle as u64,
-        params: base64::encode(Vec::new()),
-    };
-
-    Ok(pch_settle_message_api)
+    )
 }
 
 /// Utility function to generate a payment channel collect message.  Returns unsigned message.
@@ -964,52 +1740,47 @@ pub fn collect_pymtchan(
     gas_fee_cap: String,
     gas_premium: String,
 ) -> Result<UnsignedMessageAPI, SignerError> {
-    // TODO:  don't hardcode gas limit and gas price; use a gas estimator!
-    let pch_collect_message_api = UnsignedMessageAPI {
-        to: pch_address,
-        from: from_address,
+    call_message(
+        &builder::CollectPaymentChannel,
+        pch_address,
+        from_address,
         nonce,
-        value: "0".to_string(),
+        "0".to_string(),
         gas_limit,
         gas_fee_cap,
         gas_premium,
-        method: paych::MethodsPaych::Collect as u64,
-        params: base64::encode(Vec::new()),
-    };
-
-    Ok(pch_collect_message_api)
+    )
 }
 
-/// Sign a voucher for payment channel
+/// Sign a voucher for payment channel with a `Signer`
 ///
 /// # Arguments
 ///
 /// * `voucher_string` - Voucher as base64 string;
-/// * `private_key` - Private key as base64 string;
+/// * `signer` - a `Signer`;
 ///
-pub fn sign_voucher(
+pub fn sign_voucher_with_signer<S: Signer + ?Sized>(
     voucher_string: String,
-    private_key: &PrivateKey,
+    signer: &S,
 ) -> Result<String, SignerError> {
     let decoded_voucher = base64::decode(voucher_string)?;
     let mut voucher: paych::SignedVoucher = from_slice(&decoded_voucher)?;
 
-    let secret_key = secp256k1::SecretKey::parse_slice(&private_key.0)?;
-
     let svb = voucher.signing_bytes()?;
-    let digest = utils::get_digest_voucher(&svb)?;
-
-    let blob_to_sign = Message::parse_slice(&digest)?;
-
-    let (signature_rs, recovery_id) = sign(&blob_to_sign, &secret_key);
 
-    let mut signature = SignatureSECP256K1 { 0: [0; 65] };
-    signature.0[..64].copy_from_slice(&signature_rs.serialize()[..]);
-    signature.0[64] = recovery_id.serialize();
-
-    voucher.signature = Some(forest_crypto::signature::Signature::new_secp256k1(
-        signature.0.to_vec(),
-    ));
+    voucher.signature = Some(match signer.sign_bytes(&svb)? {
+        Signature::SignatureSECP256K1(signature) => {
+            forest_crypto::signature::Signature::new_secp256k1(signature.0.to_vec())
+        }
+        Signature::SignatureBLS(signature) => {
+            forest_crypto::signature::Signature::new_bls(signature.0.to_vec())
+        }
+        Signature::SignatureDelegated(_) => {
+            return Err(SignerError::GenericString(
+                "Vouchers can't have a delegated signature".to_string(),
+            ))
+        }
+    });
 
     let binary_voucher = to_vec(&voucher)?;
     let cbor_voucher = base64::encode(binary_voucher);
@@ -1017,6 +1788,22 @@ pub fn sign_voucher(
     Ok(cbor_voucher)
 }
 
+/// Sign a voucher for payment channel
+///
+/// # Arguments
+///
+/// * `voucher_string` - Voucher as base64 string;
+/// * `private_key` - Private key as base64 string;
+///
+pub fn sign_voucher(
+    voucher_string: String,
+    private_key: &PrivateKey,
+) -> Result<String, SignerError> {
+    let signer = key_recover(private_key, true)?;
+
+    sign_voucher_with_signer(voucher_string, &signer)
+}
+
 /// Create a voucher for payment channel
 ///
 /// # Arguments
@@ -1024,28 +1811,25 @@ pub fn sign_voucher(
 /// * `payment_channel_address` - The payment channel address;
 /// * `time_lock_min` - Time lock min;
 /// * `time_lock_maax` - Time lock max;
-/// * `amount` - Amount in the voucher;
+/// * `amount` - Amount in the voucher (`TokenAmount` or attoFIL string);
 /// * `lane` - Lane of the voucher;
 /// * `nonce` - Next nonce of the voucher;
 ///
-pub fn create_voucher(
+pub fn create_voucher<A: IntoTokenAmount>(
     payment_channel_address: String,
     time_lock_min: i64,
     time_lock_max: i64,
-    amount: String,
+    amount: A,
     lane: u64,
     nonce: u64,
     min_settle_height: i64,
 ) -> Result<String, SignerError> {
     let pch = Address::from_str(&payment_channel_address)?;
-    let amount = match BigInt::parse_bytes(amount.as_bytes(), 10) {
-        Some(value) => value,
-        None => {
-            return Err(SignerError::GenericString(
-                "`amount` couldn't be parsed.".to_string(),
-            ));
-        }
-    };
+    let amount = amount
+        .into_token_amount()
+        .map_err(|err| SignerError::GenericString(format!("`amount` couldn't be parsed: {}", err)))?
+        .atto()
+        .clone();
 
     let voucher = paych::SignedVoucher {
         channel_addr: pch,
@@ -1166,6 +1950,55 @@ pub fn deserialize_params(
     }
 }
 
+/// Deserialize the return value of a message (`Return` field of the message receipt). Addresses
+/// are returned with the testnet prefix (see `address::address_to_network`).
+///
+/// # Arguments
+///
+/// * `return_b64_string` - The base64 return value string;
+/// * `actor_type` - The string that tell the actor type;
+/// * `method` - Method for which we want to deserialize the return value;
+pub fn deserialize_return(
+    return_b64_string: String,
+    actor_type: String,
+    method: u64,
+) -> Result<MessageReturn, SignerError> {
+    let return_decode = base64::decode(return_b64_string)?;
+    let serialized_return = forest_vm::Serialized::new(return_decode);
+
+    match actor_type.as_str() {
+        "fil/1/init" | "fil/2/init" | "fil/3/init" | "fil/4/init" | "fil/5/init" => {
+            match FromPrimitive::from_u64(method) {
+                Some(MethodInit::Exec) => {
+                    let exec_return = serialized_return.deserialize::<ExecReturn>()?;
+
+                    Ok(MessageReturn::ExecReturn(exec_return.into()))
+                }
+                _ => Err(SignerError::GenericString(
+                    "Unknown method return for actor 'fil/1/init', 'fil/2/init', 'fil/3/init', 'fil/4/init' or 'fil/5/init'."
+                        .to_string(),
+                )),
+            }
+        }
+        "fil/2/multisig" | "fil/3/multisig" | "fil/4/multisig" | "fil/5/multisig" => {
+            match FromPrimitive::from_u64(method) {
+                Some(multisig::MethodMultisig::Propose) => {
+                    let propose_return =
+                        serialized_return.deserialize::<multisig::ProposeReturn>()?;
+
+                    Ok(MessageReturn::ProposeReturnMultisig(propose_return.into()))
+                }
+                _ => Err(SignerError::GenericString(
+                    "Unknown method return for actor 'fil/2/multisig', 'fil/3/multisig', 'fil/4/multisig' or 'fil/5/multisig'.".to_string(),
+                )),
+            }
+        }
+        _ => Err(SignerError::GenericString(
+            "Actor type not supported.".to_string(),
+        )),
+    }
+}
+
 /// Deserialize Constructor Params
 ///
 /// # Arguments
@@ -1260,26 +2093,36 @@ pub fn verify_voucher_signature(
     }
 }
 
-/// Return the CID of a message
+/// Return the CID of a message. The CID of a BLS signed message is the CID of the unsigned
+/// message.
 ///
 /// # Arguments
 ///
 /// * `message_api` - The message;
 pub fn get_cid(message_api: MessageTxAPI) -> Result<String, SignerError> {
-    use forest_encoding::Cbor;
+    Ok(message_cid(&message_api)?.to_string())
+}
 
+/// Return the CID of a message (signed or unsigned). The CID of a BLS signed message is the CID of
+/// the unsigned message.
+///
+/// # Arguments
+///
+/// * `message_api` - a signed or unsigned message
+///
+pub fn message_cid(message_api: &MessageTxAPI) -> Result<Cid, SignerError> {
     match message_api {
-        MessageTxAPI::UnsignedMessageAPI(unsigned) => {
-            let unsigned_message = UnsignedMessage::try_from(&unsigned)?;
-            let cid = unsigned_message.cid()?;
-
-            Ok(cid.to_string())
-        }
-        MessageTxAPI::SignedMessageAPI(signed) => {
-            let signed_message = SignedMessage::try_from(&signed)?;
-            let cid = signed_message.cid()?;
-
-            Ok(cid.to_string())
-        }
+        MessageTxAPI::UnsignedMessageAPI(unsigned) => AnyUnsignedMessage::try_from(unsigned)?.cid(),
+        MessageTxAPI::SignedMessageAPI(signed) => AnySignedMessage::try_from(signed)?.cid(),
     }
 }
+
+/// Return the CID bytes of a message (signed or unsigned).
+///
+/// # Arguments
+///
+/// * `message_api` - a signed or unsigned message
+///
+pub fn get_cid_bytes(message_api: &MessageTxAPI) -> Result<Vec<u8>, SignerError> {
+    Ok(message_cid(message_api)?.to_bytes())
+}
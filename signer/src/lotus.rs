@@ -0,0 +1,473 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use data_encoding::BASE32_NOPAD;
+use forest_cid::Cid;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::api::{
+    AnySignedMessage, AnyUnsignedMessage, MessageTxAPI, SignatureAPI, SignedMessageAPI,
+    UnsignedMessageAPI,
+};
+use crate::error::SignerError;
+use crate::{key_recover, key_recover_bls, ExtendedKey, PrivateKey, PublicKey};
+
+/// Prefix used by Lotus to name wallet keys in its keystore
+pub const KEY_NAME_PREFIX: &str = "wallet-";
+
+pub const KEY_TYPE_SECP256K1: &str = "secp256k1";
+pub const KEY_TYPE_BLS: &str = "bls";
+
+/// Key info as produced by `lotus wallet export` (before hex encoding) and stored in the Lotus keystore
+#[derive(PartialEq, Deserialize, Serialize)]
+pub struct LotusKeyInfo {
+    #[serde(rename = "Type")]
+    pub key_type: String,
+    /// Private key as base64 string
+    #[serde(rename = "PrivateKey")]
+    pub private_key: String,
+}
+
+impl Drop for LotusKeyInfo {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+impl fmt::Debug for LotusKeyInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LotusKeyInfo")
+            .field("key_type", &self.key_type)
+            .field("private_key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl From<&ExtendedKey> for LotusKeyInfo {
+    fn from(extended_key: &ExtendedKey) -> LotusKeyInfo {
+        let key_type = match extended_key.public_key {
+            PublicKey::PublicKeySECP256K1(_) => KEY_TYPE_SECP256K1,
+            PublicKey::BLSPublicKey(_) => KEY_TYPE_BLS,
+        };
+
+        LotusKeyInfo {
+            key_type: key_type.to_string(),
+            private_key: base64::encode(&extended_key.private_key.0),
+        }
+    }
+}
+
+impl LotusKeyInfo {
+    /// Recover the extended key, routing to `key_recover` or `key_recover_bls` depending on the key type
+    ///
+    /// # Arguments
+    ///
+    /// * `testnet` - specify the network, `true` if testnet else `false` for mainnet
+    ///
+    pub fn to_extended_key(&self, testnet: bool) -> Result<ExtendedKey, SignerError> {
+        let private_key = PrivateKey::try_from(base64::decode(&self.private_key)?)?;
+
+        match self.key_type.as_str() {
+            KEY_TYPE_SECP256K1 => key_recover(&private_key, testnet),
+            KEY_TYPE_BLS => key_recover_bls(&private_key, testnet),
+            key_type => Err(SignerError::GenericString(format!(
+                "Unsupported key type `{}`",
+                key_type
+            ))),
+        }
+    }
+}
+
+/// Returns the keystore file name Lotus uses for the given address
+/// (unpadded base32 of `wallet-<address>`)
+pub fn keystore_file_name(address: &str) -> String {
+    BASE32_NOPAD.encode(format!("{}{}", KEY_NAME_PREFIX, address).as_bytes())
+}
+
+/// Write a key in a Lotus keystore directory (e.g `~/.lotus/keystore`). Returns the file path.
+///
+/// # Arguments
+///
+/// * `keystore_path` - The keystore directory
+/// * `extended_key` - The key to store
+///
+pub fn keystore_write(
+    keystore_path: &Path,
+    extended_key: &ExtendedKey,
+) -> Result<PathBuf, SignerError> {
+    let file_path = keystore_path.join(keystore_file_name(&extended_key.address));
+
+    if file_path.exists() {
+        return Err(SignerError::GenericString(format!(
+            "Key already exists in keystore for address {}",
+            extended_key.address
+        )));
+    }
+
+    let mut key_info = serde_json::to_vec(&LotusKeyInfo::from(extended_key))?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let written = options
+        .open(&file_path)
+        .and_then(|mut file| file.write_all(&key_info));
+    key_info.zeroize();
+    written?;
+
+    Ok(file_path)
+}
+
+/// Read a key from a Lotus keystore directory
+///
+/// # Arguments
+///
+/// * `keystore_path` - The keystore directory
+/// * `address` - The address of the key
+///
+pub fn keystore_read(keystore_path: &Path, address: &str) -> Result<ExtendedKey, SignerError> {
+    let file_path = keystore_path.join(keystore_file_name(address));
+    let key_info: LotusKeyInfo = serde_json::from_slice(&fs::read(file_path)?)?;
+
+    let extended_key = key_info.to_extended_key(address.starts_with('t'))?;
+
+    if extended_key.address != address {
+        return Err(SignerError::GenericString(
+            "Keystore entry doesn't match the address".to_string(),
+        ));
+    }
+
+    Ok(extended_key)
+}
+
+/// List the wallet addresses stored in a Lotus keystore directory
+///
+/// # Arguments
+///
+/// * `keystore_path` - The keystore directory
+///
+pub fn keystore_list(keystore_path: &Path) -> Result<Vec<String>, SignerError> {
+    let mut addresses = Vec::new();
+
+    for entry in fs::read_dir(keystore_path)? {
+        let file_name = entry?.file_name();
+
+        let key_name = match file_name
+            .to_str()
+            .and_then(|name| BASE32_NOPAD.decode(name.as_bytes()).ok())
+        {
+            Some(key_name) => key_name,
+            None => continue,
+        };
+
+        if let Ok(key_name) = String::from_utf8(key_name) {
+            if let Some(address) = key_name.strip_prefix(KEY_NAME_PREFIX) {
+                addresses.push(address.to_string());
+            }
+        }
+    }
+
+    addresses.sort();
+
+    Ok(addresses)
+}
+
+/// CID as encoded in Lotus JSON (`{"/": "bafy..."}`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LotusCid {
+    #[serde(rename = "/")]
+    pub cid: String,
+}
+
+impl From<Cid> for LotusCid {
+    fn from(cid: Cid) -> LotusCid {
+        LotusCid {
+            cid: cid.to_string(),
+        }
+    }
+}
+
+/// Unsigned message in Lotus JSON format (e.g `ChainGetMessage` result)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LotusMessage {
+    #[serde(default)]
+    pub version: i64,
+    pub to: String,
+    pub from: String,
+    pub nonce: u64,
+    pub value: String,
+    pub gas_limit: i64,
+    pub gas_fee_cap: String,
+    pub gas_premium: String,
+    pub method: u64,
+    /// Base64 params, `null` when there are no params
+    #[serde(default)]
+    pub params: Option<String>,
+    #[serde(rename = "CID", default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<LotusCid>,
+}
+
+/// Signature in Lotus JSON format
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LotusSignature {
+    #[serde(rename = "Type")]
+    pub sig_type: u8,
+    #[serde(with = "crate::api::serde_base64_vector")]
+    pub data: Vec<u8>,
+}
+
+/// Signed message in Lotus JSON format (e.g `MpoolPush` payload)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LotusSignedMessage {
+    pub message: LotusMessage,
+    pub signature: LotusSignature,
+    #[serde(rename = "CID", default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<LotusCid>,
+}
+
+/// Signed or unsigned message in Lotus JSON format
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum LotusMessageTx {
+    SignedMessage(LotusSignedMessage),
+    UnsignedMessage(LotusMessage),
+}
+
+fn check_cid(cid: &Option<LotusCid>, expected: Cid) -> Result<(), SignerError> {
+    match cid {
+        Some(cid) if cid.cid != expected.to_string() => Err(SignerError::GenericString(format!(
+            "CID mismatch: expected {} but got {}",
+            expected, cid.cid
+        ))),
+        _ => Ok(()),
+    }
+}
+
+impl TryFrom<&UnsignedMessageAPI> for LotusMessage {
+    type Error = SignerError;
+
+    fn try_from(message: &UnsignedMessageAPI) -> Result<LotusMessage, Self::Error> {
+        let cid = AnyUnsignedMessage::try_from(message)?.cid()?;
+
+        Ok(LotusMessage {
+            version: 0,
+            to: message.to.clone(),
+            from: message.from.clone(),
+            nonce: message.nonce,
+            value: message.value.clone(),
+            gas_limit: message.gas_limit,
+            gas_fee_cap: message.gas_fee_cap.clone(),
+            gas_premium: message.gas_premium.clone(),
+            method: message.method,
+            params: if message.params.is_empty() {
+                None
+            } else {
+                Some(message.params.clone())
+            },
+            cid: Some(cid.into()),
+        })
+    }
+}
+
+impl TryFrom<&LotusMessage> for UnsignedMessageAPI {
+    type Error = SignerError;
+
+    /// Fails if the message version isn't `0` or if the `CID` field doesn't match the message
+    fn try_from(message: &LotusMessage) -> Result<UnsignedMessageAPI, Self::Error> {
+        if message.version != 0 {
+            return Err(SignerError::GenericString(format!(
+                "Unsupported message version {}",
+                message.version
+            )));
+        }
+
+        let message_api = UnsignedMessageAPI {
+            to: message.to.clone(),
+            from: message.from.clone(),
+            nonce: message.nonce,
+            value: message.value.clone(),
+            gas_limit: message.gas_limit,
+            gas_fee_cap: message.gas_fee_cap.clone(),
+            gas_premium: message.gas_premium.clone(),
+            method: message.method,
+            params: message.params.clone().unwrap_or_default(),
+        };
+
+        check_cid(
+            &message.cid,
+            AnyUnsignedMessage::try_from(&message_api)?.cid()?,
+        )?;
+
+        Ok(message_api)
+    }
+}
+
+impl TryFrom<&SignedMessageAPI> for LotusSignedMessage {
+    type Error = SignerError;
+
+    fn try_from(signed_message: &SignedMessageAPI) -> Result<LotusSignedMessage, Self::Error> {
+        let cid = AnySignedMessage::try_from(signed_message)?.cid()?;
+
+        Ok(LotusSignedMessage {
+            message: LotusMessage::try_from(&signed_message.message)?,
+            signature: LotusSignature {
+                sig_type: signed_message.signature.sig_type,
+                data: signed_message.signature.data.clone(),
+            },
+            cid: Some(cid.into()),
+        })
+    }
+}
+
+impl TryFrom<&LotusSignedMessage> for SignedMessageAPI {
+    type Error = SignerError;
+
+    /// Fails if the message is invalid or if the `CID` field doesn't match the signed message
+    fn try_from(signed_message: &LotusSignedMessage) -> Result<SignedMessageAPI, Self::Error> {
+        let signed_message_api = SignedMessageAPI {
+            message: UnsignedMessageAPI::try_from(&signed_message.message)?,
+            signature: SignatureAPI {
+                sig_type: signed_message.signature.sig_type,
+                data: signed_message.signature.data.clone(),
+            },
+        };
+
+        check_cid(
+            &signed_message.cid,
+            AnySignedMessage::try_from(&signed_message_api)?.cid()?,
+        )?;
+
+        Ok(signed_message_api)
+    }
+}
+
+impl TryFrom<&MessageTxAPI> for LotusMessageTx {
+    type Error = SignerError;
+
+    fn try_from(message: &MessageTxAPI) -> Result<LotusMessageTx, Self::Error> {
+        match message {
+            MessageTxAPI::UnsignedMessageAPI(message) => Ok(LotusMessageTx::UnsignedMessage(
+                LotusMessage::try_from(message)?,
+            )),
+            MessageTxAPI::SignedMessageAPI(signed_message) => Ok(LotusMessageTx::SignedMessage(
+                LotusSignedMessage::try_from(signed_message)?,
+            )),
+        }
+    }
+}
+
+impl TryFrom<&LotusMessageTx> for MessageTxAPI {
+    type Error = SignerError;
+
+    fn try_from(message: &LotusMessageTx) -> Result<MessageTxAPI, Self::Error> {
+        match message {
+            LotusMessageTx::UnsignedMessage(message) => Ok(MessageTxAPI::UnsignedMessageAPI(
+                UnsignedMessageAPI::try_from(message)?,
+            )),
+            LotusMessageTx::SignedMessage(signed_message) => Ok(MessageTxAPI::SignedMessageAPI(
+                SignedMessageAPI::try_from(signed_message)?,
+            )),
+        }
+    }
+}
+
+/// Serialize a message (signed or unsigned) in Lotus JSON format, with its `CID`.
+///
+/// # Arguments
+///
+/// * `message` - a signed or unsigned message
+///
+pub fn message_to_json(message: &MessageTxAPI) -> Result<String, SignerError> {
+    Ok(serde_json::to_string(&LotusMessageTx::try_from(message)?)?)
+}
+
+/// Parse a message (signed or unsigned) in Lotus JSON format. The `CID` field is optional but, if
+/// present, must match the message.
+///
+/// # Arguments
+///
+/// * `json` - a Lotus JSON message (e.g a `ChainGetMessage` result or a `MpoolPush` payload)
+///
+pub fn message_from_json(json: &str) -> Result<MessageTxAPI, SignerError> {
+    let message: LotusMessageTx = serde_json::from_str(json)?;
+
+    MessageTxAPI::try_from(&message)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::MessageTxAPI;
+    use crate::lotus::{keystore_file_name, message_from_json, message_to_json};
+    use data_encoding::BASE32_NOPAD;
+
+    const LOTUS_SIGNED_MESSAGE: &str = r#"{"Message":{"Version":0,"To":"t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy","From":"t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba","Nonce":1,"Value":"100000","GasLimit":2500000,"GasFeeCap":"1","GasPremium":"1","Method":0,"Params":null,"CID":{"/":"CID_UNSIGNED"}},"Signature":{"Type":1,"Data":"0wRrFJZFIVh8m0JD+f5C55YrxD6YAWtCXWYihrPTKdMfgMhYAy86MVhs43hSLXnV+47UReRIe8qFdHRJqFlreAE="},"CID":{"/":"bafy2bzacebaiinljwwctblf7czp4zxwhz4747z6tpricgn5cumd4xhebftcvu"}}"#;
+
+    #[test]
+    fn lotus_json_roundtrip() {
+        let without_cids = LOTUS_SIGNED_MESSAGE
+            .replace(r#","CID":{"/":"CID_UNSIGNED"}"#, "")
+            .replace(
+                r#","CID":{"/":"bafy2bzacebaiinljwwctblf7czp4zxwhz4747z6tpricgn5cumd4xhebftcvu"}"#,
+                "",
+            );
+
+        let signed_message = match message_from_json(&without_cids).unwrap() {
+            MessageTxAPI::SignedMessageAPI(signed_message) => signed_message,
+            MessageTxAPI::UnsignedMessageAPI(_) => panic!("Should be a signed message"),
+        };
+        assert_eq!(signed_message.signature.sig_type, 1);
+        assert_eq!(signed_message.message.params, "");
+
+        // Serialized with the CIDs, exactly as Lotus does
+        let unsigned_cid = crate::get_cid(MessageTxAPI::UnsignedMessageAPI(
+            signed_message.message.clone(),
+        ))
+        .unwrap();
+        let expected = LOTUS_SIGNED_MESSAGE.replace("CID_UNSIGNED", &unsigned_cid);
+
+        let json =
+            message_to_json(&MessageTxAPI::SignedMessageAPI(signed_message.clone())).unwrap();
+        assert_eq!(json, expected);
+
+        match message_from_json(&json).unwrap() {
+            MessageTxAPI::SignedMessageAPI(parsed) => assert_eq!(parsed, signed_message),
+            MessageTxAPI::UnsignedMessageAPI(_) => panic!("Should be a signed message"),
+        }
+
+        // Unsigned message
+        let message = MessageTxAPI::UnsignedMessageAPI(signed_message.message);
+        match message_from_json(&message_to_json(&message).unwrap()).unwrap() {
+            MessageTxAPI::UnsignedMessageAPI(parsed) => assert_eq!(parsed, message.get_message()),
+            MessageTxAPI::SignedMessageAPI(_) => panic!("Should be an unsigned message"),
+        }
+
+        // Wrong CID
+        assert!(message_from_json(&expected.replace(
+            &unsigned_cid,
+            "bafy2bzacebaiinljwwctblf7czp4zxwhz4747z6tpricgn5cumd4xhebftcvu"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn keystore_name() {
+        let name = keystore_file_name("t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba");
+
+        assert_eq!(
+            BASE32_NOPAD.decode(name.as_bytes()).unwrap(),
+            b"wallet-t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba".to_vec()
+        );
+    }
+}
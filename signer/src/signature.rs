@@ -14,6 +14,8 @@ pub struct SignatureBLS(pub [u8; BLS_SIGNATURE_SIZE]);
 pub enum Signature {
     SignatureSECP256K1(SignatureSECP256K1),
     SignatureBLS(SignatureBLS),
+    /// Delegated signature: RSV secp256k1 signature over a keccak-256 digest (Ethereum transaction)
+    SignatureDelegated(SignatureSECP256K1),
 }
 
 impl Signature {
@@ -21,6 +23,7 @@ impl Signature {
         match self {
             Signature::SignatureSECP256K1(sig_secp256k1) => sig_secp256k1.as_bytes(),
             Signature::SignatureBLS(sig_bls) => sig_bls.as_bytes(),
+            Signature::SignatureDelegated(sig_delegated) => sig_delegated.as_bytes(),
         }
     }
 }
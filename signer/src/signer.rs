@@ -0,0 +1,102 @@
+use std::convert::TryFrom;
+
+use bls_signatures::Serialize;
+use forest_address::Protocol;
+use forest_encoding::blake2b_256;
+use secp256k1::{sign, Message};
+
+use crate::error::SignerError;
+use crate::signature::{Signature, SignatureBLS, SignatureSECP256K1};
+use crate::{ExtendedKey, PublicKey};
+
+/// Key type of a signer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyType {
+    Secp256k1,
+    BLS,
+}
+
+impl KeyType {
+    /// Address protocol matching the key type
+    pub fn address_protocol(self) -> Protocol {
+        match self {
+            KeyType::Secp256k1 => Protocol::Secp256k1,
+            KeyType::BLS => Protocol::BLS,
+        }
+    }
+}
+
+/// Signing backend (in-memory key, HSM, remote signer, hardware wallet...)
+///
+/// Implementors override `sign_digest` for secp256k1 keys and `sign_bytes` for BLS keys.
+pub trait Signer {
+    /// Address of the key
+    fn address(&self) -> &str;
+
+    /// Type of the key
+    fn key_type(&self) -> KeyType;
+
+    /// Sign a 32 bytes digest (secp256k1 only)
+    fn sign_digest(&self, _digest: &[u8; 32]) -> Result<Signature, SignerError> {
+        Err(SignerError::GenericString(
+            "Signer can't sign a digest".to_string(),
+        ))
+    }
+
+    /// Sign bytes following Filecoin rules: secp256k1 signs the blake2b-256 digest of the bytes,
+    /// BLS signs the bytes directly
+    fn sign_bytes(&self, bytes: &[u8]) -> Result<Signature, SignerError> {
+        match self.key_type() {
+            KeyType::Secp256k1 => self.sign_digest(&blake2b_256(bytes)),
+            KeyType::BLS => Err(SignerError::GenericString(
+                "BLS signers must implement `sign_bytes`".to_string(),
+            )),
+        }
+    }
+}
+
+impl Signer for ExtendedKey {
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    fn key_type(&self) -> KeyType {
+        match self.public_key {
+            PublicKey::PublicKeySECP256K1(_) => KeyType::Secp256k1,
+            PublicKey::BLSPublicKey(_) => KeyType::BLS,
+        }
+    }
+
+    fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature, SignerError> {
+        if self.key_type() != KeyType::Secp256k1 {
+            return Err(SignerError::GenericString(
+                "Only secp256k1 keys can sign a digest".to_string(),
+            ));
+        }
+
+        let secret_key = secp256k1::SecretKey::parse_slice(&self.private_key.0)?;
+        let message_digest = Message::parse_slice(digest)?;
+
+        let (signature_rs, recovery_id) = sign(&message_digest, &secret_key);
+
+        let mut signature = SignatureSECP256K1 { 0: [0; 65] };
+        signature.0[..64].copy_from_slice(&signature_rs.serialize()[..]);
+        signature.0[64] = recovery_id.serialize();
+
+        Ok(Signature::SignatureSECP256K1(signature))
+    }
+
+    fn sign_bytes(&self, bytes: &[u8]) -> Result<Signature, SignerError> {
+        match self.key_type() {
+            KeyType::Secp256k1 => self.sign_digest(&blake2b_256(bytes)),
+            KeyType::BLS => {
+                let sk = bls_signatures::PrivateKey::from_bytes(&self.private_key.0)?;
+                let sig = sk.sign(bytes);
+
+                Ok(Signature::SignatureBLS(SignatureBLS::try_from(
+                    sig.as_bytes(),
+                )?))
+            }
+        }
+    }
+}
@@ -0,0 +1,417 @@
+use std::fmt;
+use std::str::FromStr;
+
+use num_bigint_chainsafe::{BigInt, Sign};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::SignerError;
+
+/// Unit of a FIL amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenUnit {
+    FIL,
+    MilliFIL,
+    MicroFIL,
+    NanoFIL,
+    PicoFIL,
+    FemtoFIL,
+    AttoFIL,
+}
+
+const UNITS: [TokenUnit; 7] = [
+    TokenUnit::FIL,
+    TokenUnit::MilliFIL,
+    TokenUnit::MicroFIL,
+    TokenUnit::NanoFIL,
+    TokenUnit::PicoFIL,
+    TokenUnit::FemtoFIL,
+    TokenUnit::AttoFIL,
+];
+
+impl TokenUnit {
+    /// Number of decimals of the unit (1 unit = 10^decimals attoFIL)
+    pub fn decimals(self) -> usize {
+        match self {
+            TokenUnit::FIL => 18,
+            TokenUnit::MilliFIL => 15,
+            TokenUnit::MicroFIL => 12,
+            TokenUnit::NanoFIL => 9,
+            TokenUnit::PicoFIL => 6,
+            TokenUnit::FemtoFIL => 3,
+            TokenUnit::AttoFIL => 0,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TokenUnit::FIL => "FIL",
+            TokenUnit::MilliFIL => "milliFIL",
+            TokenUnit::MicroFIL => "microFIL",
+            TokenUnit::NanoFIL => "nanoFIL",
+            TokenUnit::PicoFIL => "picoFIL",
+            TokenUnit::FemtoFIL => "femtoFIL",
+            TokenUnit::AttoFIL => "attoFIL",
+        }
+    }
+}
+
+impl fmt::Display for TokenUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl FromStr for TokenUnit {
+    type Err = SignerError;
+
+    /// Parse a unit symbol (case insensitive, e.g `FIL`, `milliFIL` or `attofil`)
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        UNITS
+            .iter()
+            .find(|unit| unit.symbol().eq_ignore_ascii_case(symbol))
+            .copied()
+            .ok_or_else(|| SignerError::GenericString(format!("Unknown FIL unit `{}`", symbol)))
+    }
+}
+
+/// A non-negative amount of FIL, stored in attoFIL (10^-18 FIL)
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TokenAmount(BigInt);
+
+impl TokenAmount {
+    /// Create an amount from attoFIL, fails if negative
+    pub fn from_atto(atto: BigInt) -> Result<Self, SignerError> {
+        if atto.sign() == Sign::Minus {
+            return Err(SignerError::GenericString(
+                "Token amount can't be negative".to_string(),
+            ));
+        }
+
+        Ok(TokenAmount(atto))
+    }
+
+    /// Create an amount of whole FIL
+    pub fn from_fil(fil: u64) -> Self {
+        TokenAmount(BigInt::from(fil) * BigInt::from(10u64.pow(18)))
+    }
+
+    /// Parse an exact decimal amount (e.g `1.5`) expressed in `unit`. Fails if the amount has
+    /// more decimals than the unit allows.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - decimal amount without unit
+    /// * `unit` - the unit of `amount`
+    ///
+    pub fn parse_with_unit(amount: &str, unit: TokenUnit) -> Result<Self, SignerError> {
+        let invalid = || SignerError::GenericString(format!("Invalid token amount `{}`", amount));
+
+        let (integer, fraction) = match amount.find('.') {
+            Some(dot) => (&amount[..dot], &amount[dot + 1..]),
+            None => (amount, ""),
+        };
+
+        if integer.is_empty()
+            || !integer.bytes().all(|c| c.is_ascii_digit())
+            || (amount.contains('.') && fraction.is_empty())
+            || !fraction.bytes().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        // Extra decimals are only accepted if they are zeros, the amount must be exact
+        let decimals = unit.decimals();
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > decimals {
+            return Err(SignerError::GenericString(format!(
+                "Token amount `{}` has more than {} decimals for unit {}",
+                amount, decimals, unit
+            )));
+        }
+
+        let atto = format!(
+            "{}{}{}",
+            integer,
+            fraction,
+            "0".repeat(decimals - fraction.len())
+        );
+
+        Ok(TokenAmount(BigInt::from_str(&atto).map_err(|_| invalid())?))
+    }
+
+    /// The amount in attoFIL
+    pub fn atto(&self) -> &BigInt {
+        &self.0
+    }
+
+    /// The amount in attoFIL as a decimal string, the format of the message and voucher fields
+    pub fn to_atto_string(&self) -> String {
+        self.0.to_str_radix(10)
+    }
+
+    /// Format the amount in `unit` without rounding (e.g `1.5 FIL`)
+    pub fn format(&self, unit: TokenUnit) -> String {
+        let decimals = unit.decimals();
+        let mut atto = self.to_atto_string();
+        if atto.len() <= decimals {
+            atto = format!("{}{}", "0".repeat(decimals + 1 - atto.len()), atto);
+        }
+
+        let (integer, fraction) = atto.split_at(atto.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            format!("{} {}", integer, unit)
+        } else {
+            format!("{}.{} {}", integer, fraction, unit)
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.sign() == Sign::NoSign
+    }
+
+    pub fn checked_add(&self, other: &TokenAmount) -> Option<TokenAmount> {
+        Some(TokenAmount(&self.0 + &other.0))
+    }
+
+    /// Subtract `other`, `None` if the result would be negative
+    pub fn checked_sub(&self, other: &TokenAmount) -> Option<TokenAmount> {
+        if other.0 > self.0 {
+            return None;
+        }
+
+        Some(TokenAmount(&self.0 - &other.0))
+    }
+
+    pub fn checked_mul(&self, factor: u64) -> Option<TokenAmount> {
+        Some(TokenAmount(&self.0 * BigInt::from(factor)))
+    }
+
+    /// Divide by `divisor` (rounding down), `None` if `divisor` is 0
+    pub fn checked_div(&self, divisor: u64) -> Option<TokenAmount> {
+        if divisor == 0 {
+            return None;
+        }
+
+        Some(TokenAmount(&self.0 / BigInt::from(divisor)))
+    }
+}
+
+impl From<u64> for TokenAmount {
+    /// Amount in attoFIL
+    fn from(atto: u64) -> Self {
+        TokenAmount(BigInt::from(atto))
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format(TokenUnit::FIL))
+    }
+}
+
+impl FromStr for TokenAmount {
+    type Err = SignerError;
+
+    /// Parse an amount with a unit (e.g `1.5 FIL`, `250milliFIL`). An amount without unit is an
+    /// integer in attoFIL, like the message and voucher fields.
+    fn from_str(amount: &str) -> Result<Self, Self::Err> {
+        let amount = amount.trim();
+        let split = amount
+            .find(|c: char| c.is_ascii_alphabetic())
+            .unwrap_or(amount.len());
+        let (value, unit) = amount.split_at(split);
+
+        if unit.is_empty() {
+            TokenAmount::parse_with_unit(value, TokenUnit::AttoFIL)
+        } else {
+            TokenAmount::parse_with_unit(value.trim_end(), TokenUnit::from_str(unit)?)
+        }
+    }
+}
+
+impl Serialize for TokenAmount {
+    /// Serialized as a string in attoFIL
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_atto_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenAmount {
+    /// Deserialized from a string in attoFIL or with a unit
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let amount = String::deserialize(deserializer)?;
+        TokenAmount::from_str(&amount).map_err(de::Error::custom)
+    }
+}
+
+/// Serde adapter for `TokenAmount` fields serialized in FIL (e.g `"1.5 FIL"`), use with
+/// `#[serde(with = "filecoin_signer::token::serde_fil")]`
+pub mod serde_fil {
+    use super::*;
+
+    pub fn serialize<S>(amount: &TokenAmount, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&amount.format(TokenUnit::FIL))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<TokenAmount, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        TokenAmount::deserialize(deserializer)
+    }
+}
+
+/// Serde adapter for attoFIL string fields (e.g `UnsignedMessageAPI.value`) that also accepts
+/// amounts with a unit (e.g `"1.5 FIL"`), use with
+/// `#[serde(with = "filecoin_signer::token::serde_atto_string")]`
+pub mod serde_atto_string {
+    use super::*;
+
+    pub fn serialize<S>(amount: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(amount)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(TokenAmount::deserialize(deserializer)?.to_atto_string())
+    }
+}
+
+/// Conversion into a `TokenAmount`, so the message builders accept either a `TokenAmount` or a
+/// string (see `TokenAmount::from_str`)
+pub trait IntoTokenAmount {
+    fn into_token_amount(self) -> Result<TokenAmount, SignerError>;
+}
+
+impl IntoTokenAmount for TokenAmount {
+    fn into_token_amount(self) -> Result<TokenAmount, SignerError> {
+        Ok(self)
+    }
+}
+
+impl IntoTokenAmount for &TokenAmount {
+    fn into_token_amount(self) -> Result<TokenAmount, SignerError> {
+        Ok(self.clone())
+    }
+}
+
+impl IntoTokenAmount for String {
+    fn into_token_amount(self) -> Result<TokenAmount, SignerError> {
+        TokenAmount::from_str(&self)
+    }
+}
+
+impl IntoTokenAmount for &String {
+    fn into_token_amount(self) -> Result<TokenAmount, SignerError> {
+        TokenAmount::from_str(self)
+    }
+}
+
+impl IntoTokenAmount for &str {
+    fn into_token_amount(self) -> Result<TokenAmount, SignerError> {
+        TokenAmount::from_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_units() {
+        let amount = TokenAmount::from_str("1.5 FIL").unwrap();
+
+        assert_eq!(amount.to_atto_string(), "1500000000000000000");
+        assert_eq!(TokenAmount::from_str("1500milliFIL").unwrap(), amount);
+        assert_eq!(TokenAmount::from_str("1500000000 nanofil").unwrap(), amount);
+        assert_eq!(
+            TokenAmount::from_str("1500000000000000000 attoFIL").unwrap(),
+            amount
+        );
+        assert_eq!(
+            TokenAmount::from_str("1500000000000000000").unwrap(),
+            amount
+        );
+        assert_eq!(TokenAmount::from_str("1.50 FIL").unwrap(), amount);
+
+        // Not exact or not an amount
+        assert!(TokenAmount::from_str("1.5").is_err());
+        assert!(TokenAmount::from_str("0.5 attoFIL").is_err());
+        assert!(TokenAmount::from_str("0.0000000000000000001 FIL").is_err());
+        assert!(TokenAmount::from_str("-1 FIL").is_err());
+        assert!(TokenAmount::from_str(".5 FIL").is_err());
+        assert!(TokenAmount::from_str("1. FIL").is_err());
+        assert!(TokenAmount::from_str("1 FILL").is_err());
+        assert!(TokenAmount::from_str("").is_err());
+    }
+
+    #[test]
+    fn format_units() {
+        let amount = TokenAmount::from_str("1.5 FIL").unwrap();
+
+        assert_eq!(amount.to_string(), "1.5 FIL");
+        assert_eq!(amount.format(TokenUnit::MilliFIL), "1500 milliFIL");
+        assert_eq!(
+            TokenAmount::from(1u64).to_string(),
+            "0.000000000000000001 FIL"
+        );
+        assert_eq!(TokenAmount::default().to_string(), "0 FIL");
+        assert_eq!(
+            TokenAmount::from_fil(2).format(TokenUnit::AttoFIL),
+            "2000000000000000000 attoFIL"
+        );
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let one = TokenAmount::from_fil(1);
+        let half = TokenAmount::from_str("0.5 FIL").unwrap();
+
+        assert_eq!(one.checked_sub(&half).unwrap(), half);
+        assert!(half.checked_sub(&one).is_none());
+        assert_eq!(half.checked_add(&half).unwrap(), one);
+        assert_eq!(half.checked_mul(2).unwrap(), one);
+        assert_eq!(one.checked_div(2).unwrap(), half);
+        assert!(one.checked_div(0).is_none());
+        assert!(TokenAmount::from_atto(BigInt::from(-1)).is_err());
+    }
+
+    #[test]
+    fn serde_adapters() {
+        #[derive(Serialize, Deserialize)]
+        struct Amounts {
+            atto: TokenAmount,
+            #[serde(with = "serde_fil")]
+            fil: TokenAmount,
+            #[serde(with = "serde_atto_string")]
+            value: String,
+        }
+
+        let amounts: Amounts = serde_json::from_str(
+            r#"{ "atto": "1.5 FIL", "fil": "1500000000000000000", "value": "2 milliFIL" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(amounts.value, "2000000000000000");
+        assert_eq!(
+            serde_json::to_string(&amounts).unwrap(),
+            r#"{"atto":"1500000000000000000","fil":"1.5 FIL","value":"2000000000000000"}"#
+        );
+    }
+}
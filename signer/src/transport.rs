@@ -0,0 +1,520 @@
+use std::collections::BTreeSet;
+
+use data_encoding::BASE32_NOPAD;
+use forest_encoding::tuple::*;
+use sha2::{Digest, Sha256};
+
+use crate::api::MessageTxAPI;
+use crate::error::SignerError;
+use crate::{signed_message_serialize, transaction_parse, transaction_serialize, CborBuffer};
+
+/// Prefix of the text parts (uppercase to fit the QR alphanumeric mode)
+pub const PART_PREFIX: &str = "FILTX";
+
+/// Largest message accepted by the decoder
+pub const MAX_MESSAGE_LEN: usize = 1 << 20;
+
+/// CRC-32 (ISO-HDLC) checksum
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (!(crc & 1)).wrapping_add(1);
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+/// Xoshiro256** generator, seeded from the part sequence number and message checksum so the
+/// encoder and the decoder choose the same fragments
+struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    fn new(seq_num: u32, checksum: u32) -> Self {
+        let mut seed = Vec::with_capacity(8);
+        seed.extend_from_slice(&seq_num.to_be_bytes());
+        seed.extend_from_slice(&checksum.to_be_bytes());
+        let digest = Sha256::digest(&seed);
+
+        let mut s = [0u64; 4];
+        for (i, word) in s.iter_mut().enumerate() {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&digest[i * 8..(i + 1) * 8]);
+            *word = u64::from_be_bytes(bytes);
+        }
+
+        Xoshiro256 { s }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        result
+    }
+
+    /// Random number in `[0, 1)`
+    fn next_double(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Random number in `[0, bound)`
+    fn next_index(&mut self, bound: usize) -> usize {
+        ((self.next_double() * bound as f64) as usize).min(bound - 1)
+    }
+}
+
+/// Indexes of the fragments mixed in a part. The first `seq_len` parts are the fragments in order,
+/// the next ones XOR a random set of fragments (the degree follows a 1/i distribution).
+fn choose_fragments(seq_num: u32, seq_len: usize, checksum: u32) -> BTreeSet<usize> {
+    if seq_num as usize <= seq_len {
+        return std::iter::once(seq_num as usize - 1).collect();
+    }
+
+    let mut rng = Xoshiro256::new(seq_num, checksum);
+
+    let total: f64 = (1..=seq_len).map(|i| 1.0 / i as f64).sum();
+    let mut target = rng.next_double() * total;
+    let mut degree = seq_len;
+    for i in 1..=seq_len {
+        target -= 1.0 / i as f64;
+        if target < 0.0 {
+            degree = i;
+            break;
+        }
+    }
+
+    let mut indexes: Vec<usize> = (0..seq_len).collect();
+    for i in (1..seq_len).rev() {
+        indexes.swap(i, rng.next_index(i + 1));
+    }
+
+    indexes.into_iter().take(degree).collect()
+}
+
+fn xor_into(target: &mut [u8], other: &[u8]) {
+    for (byte, other_byte) in target.iter_mut().zip(other) {
+        *byte ^= other_byte;
+    }
+}
+
+/// Fragment of a fountain coded message
+#[derive(Debug, Clone, PartialEq, Serialize_tuple, Deserialize_tuple)]
+pub struct FountainPart {
+    /// Sequence number of the part, starting at 1
+    pub seq_num: u32,
+    /// Number of fragments of the message
+    pub seq_len: u32,
+    pub message_len: u32,
+    /// CRC-32 of the message
+    pub checksum: u32,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+impl FountainPart {
+    fn fragment_len(&self) -> usize {
+        (self.message_len as usize + self.seq_len as usize - 1) / self.seq_len as usize
+    }
+
+    /// Text encoding of the part: `FILTX/<seq_num>-<seq_len>/<base32 CBOR>`
+    pub fn to_qr_string(&self) -> Result<String, SignerError> {
+        Ok(format!(
+            "{}/{}-{}/{}",
+            PART_PREFIX,
+            self.seq_num,
+            self.seq_len,
+            BASE32_NOPAD.encode(&forest_encoding::to_vec(self)?)
+        ))
+    }
+
+    /// Parse the text encoding of a part
+    pub fn from_qr_string(part: &str) -> Result<Self, SignerError> {
+        let invalid = || SignerError::GenericString("Invalid QR part".to_string());
+
+        let mut components = part.trim().split('/');
+        let (prefix, header, payload) =
+            match (components.next(), components.next(), components.next()) {
+                (Some(prefix), Some(header), Some(payload)) if components.next().is_none() => {
+                    (prefix, header, payload)
+                }
+                _ => return Err(invalid()),
+            };
+
+        if !prefix.eq_ignore_ascii_case(PART_PREFIX) {
+            return Err(invalid());
+        }
+
+        let cbor = BASE32_NOPAD
+            .decode(payload.to_ascii_uppercase().as_bytes())
+            .map_err(|_| invalid())?;
+        let part: FountainPart = forest_encoding::from_slice(&cbor)?;
+
+        if header != format!("{}-{}", part.seq_num, part.seq_len) {
+            return Err(invalid());
+        }
+
+        Ok(part)
+    }
+}
+
+/// Split a message in fragments of about `max_fragment_len` bytes and produce an endless stream
+/// of parts: the fragments, then random combinations of them. Any set of parts slightly larger
+/// than the fragments is usually enough to rebuild the message, in any order.
+#[derive(Debug, Clone)]
+pub struct FountainEncoder {
+    message_len: u32,
+    checksum: u32,
+    fragments: Vec<Vec<u8>>,
+    seq_num: u32,
+}
+
+impl FountainEncoder {
+    pub fn new(message: &[u8], max_fragment_len: usize) -> Result<Self, SignerError> {
+        if message.is_empty() || message.len() > MAX_MESSAGE_LEN {
+            return Err(SignerError::GenericString(format!(
+                "Message length must be between 1 and {} bytes",
+                MAX_MESSAGE_LEN
+            )));
+        }
+        if max_fragment_len == 0 {
+            return Err(SignerError::GenericString(
+                "Fragment length must be positive".to_string(),
+            ));
+        }
+
+        let fragment_count = (message.len() + max_fragment_len - 1) / max_fragment_len;
+        let fragment_len = (message.len() + fragment_count - 1) / fragment_count;
+
+        let fragments = message
+            .chunks(fragment_len)
+            .map(|chunk| {
+                let mut fragment = chunk.to_vec();
+                fragment.resize(fragment_len, 0);
+                fragment
+            })
+            .collect();
+
+        Ok(FountainEncoder {
+            message_len: message.len() as u32,
+            checksum: crc32(message),
+            fragments,
+            seq_num: 0,
+        })
+    }
+
+    /// Number of fragments (the minimum number of parts to scan)
+    pub fn seq_len(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// Part with the given sequence number (starting at 1)
+    pub fn part(&self, seq_num: u32) -> FountainPart {
+        let fragment_len = self.fragments.first().map_or(0, Vec::len);
+        let mut data = vec![0u8; fragment_len];
+
+        for index in choose_fragments(seq_num.max(1), self.seq_len(), self.checksum) {
+            xor_into(&mut data, &self.fragments[index]);
+        }
+
+        FountainPart {
+            seq_num: seq_num.max(1),
+            seq_len: self.seq_len() as u32,
+            message_len: self.message_len,
+            checksum: self.checksum,
+            data,
+        }
+    }
+
+    /// Next part of the stream
+    pub fn next_part(&mut self) -> FountainPart {
+        self.seq_num = self.seq_num.wrapping_add(1).max(1);
+        self.part(self.seq_num)
+    }
+}
+
+/// Rebuild a message from fountain coded parts received in any order
+#[derive(Debug, Clone, Default)]
+pub struct FountainDecoder {
+    seq_len: usize,
+    message_len: u32,
+    checksum: u32,
+    fragment_len: usize,
+    fragments: Vec<Option<Vec<u8>>>,
+    /// Parts mixing fragments not solved yet
+    mixed: Vec<(BTreeSet<usize>, Vec<u8>)>,
+    received: BTreeSet<u32>,
+}
+
+impl FountainDecoder {
+    pub fn new() -> Self {
+        FountainDecoder::default()
+    }
+
+    /// Add a part. Return `true` once the message is complete. Parts of another message are an
+    /// error.
+    pub fn receive(&mut self, part: &FountainPart) -> Result<bool, SignerError> {
+        if part.seq_num == 0
+            || part.seq_len == 0
+            || part.message_len < part.seq_len
+            || part.message_len as usize > MAX_MESSAGE_LEN
+            || part.data.len() != part.fragment_len()
+        {
+            return Err(SignerError::GenericString("Invalid part".to_string()));
+        }
+
+        if self.fragments.is_empty() {
+            self.seq_len = part.seq_len as usize;
+            self.message_len = part.message_len;
+            self.checksum = part.checksum;
+            self.fragment_len = part.fragment_len();
+            self.fragments = vec![None; self.seq_len];
+        } else if part.seq_len as usize != self.seq_len
+            || part.message_len != self.message_len
+            || part.checksum != self.checksum
+        {
+            return Err(SignerError::GenericString(
+                "Part of another message".to_string(),
+            ));
+        }
+
+        if self.is_complete() || !self.received.insert(part.seq_num) {
+            return Ok(self.is_complete());
+        }
+
+        let indexes = choose_fragments(part.seq_num, self.seq_len, self.checksum);
+        self.mixed.push((indexes, part.data.clone()));
+        self.reduce();
+
+        Ok(self.is_complete())
+    }
+
+    /// Remove the solved fragments from the mixed parts until no new fragment is solved
+    fn reduce(&mut self) {
+        let fragments = &mut self.fragments;
+
+        loop {
+            let mut solved = false;
+
+            for (indexes, data) in self.mixed.iter_mut() {
+                let known: Vec<usize> = indexes
+                    .iter()
+                    .copied()
+                    .filter(|index| fragments[*index].is_some())
+                    .collect();
+
+                for index in known {
+                    if let Some(fragment) = &fragments[index] {
+                        xor_into(data, fragment);
+                    }
+                    indexes.remove(&index);
+                }
+
+                if indexes.len() == 1 {
+                    if let Some(index) = indexes.iter().next().copied() {
+                        fragments[index] = Some(data.clone());
+                        indexes.clear();
+                        solved = true;
+                    }
+                }
+            }
+
+            self.mixed.retain(|(indexes, _)| !indexes.is_empty());
+
+            if !solved {
+                break;
+            }
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        !self.fragments.is_empty() && self.fragments.iter().all(Option::is_some)
+    }
+
+    /// Fraction of the fragments solved
+    pub fn progress(&self) -> f64 {
+        if self.fragments.is_empty() {
+            return 0.0;
+        }
+
+        self.fragments.iter().filter(|f| f.is_some()).count() as f64 / self.seq_len as f64
+    }
+
+    /// The rebuilt message, once complete. Fails if the checksum doesn't match.
+    pub fn message(&self) -> Result<Vec<u8>, SignerError> {
+        if !self.is_complete() {
+            return Err(SignerError::GenericString(
+                "Message not complete".to_string(),
+            ));
+        }
+
+        let mut message: Vec<u8> = self.fragments.iter().flatten().flatten().copied().collect();
+        message.truncate(self.message_len as usize);
+
+        if crc32(&message) != self.checksum {
+            return Err(SignerError::GenericString(
+                "Invalid message checksum".to_string(),
+            ));
+        }
+
+        Ok(message)
+    }
+}
+
+/// Compact binary encoding of a message: the CBOR of the unsigned message
+/// (`transaction_serialize`) or of the signed message (`signed_message_serialize`).
+///
+/// # Arguments
+///
+/// * `message` - a signed or unsigned message
+///
+pub fn message_to_bytes(message: &MessageTxAPI) -> Result<Vec<u8>, SignerError> {
+    let cbor = match message {
+        MessageTxAPI::UnsignedMessageAPI(unsigned_message) => {
+            transaction_serialize(unsigned_message)?
+        }
+        MessageTxAPI::SignedMessageAPI(signed_message) => signed_message_serialize(signed_message)?,
+    };
+
+    Ok(cbor.0)
+}
+
+/// Parse the binary encoding of a message with `transaction_parse`.
+///
+/// # Arguments
+///
+/// * `bytes` - the CBOR of a signed or unsigned message
+/// * `testnet` - boolean value `true` if testnet or `false` for mainnet
+///
+pub fn message_from_bytes(bytes: &[u8], testnet: bool) -> Result<MessageTxAPI, SignerError> {
+    transaction_parse(&CborBuffer(bytes.to_vec()), testnet)
+}
+
+/// Encode a message in `count` QR parts (at least the number of fragments). Parts after the
+/// fragments are fountain coded, so a scanner can miss some of them.
+///
+/// # Arguments
+///
+/// * `message` - a signed or unsigned message
+/// * `max_fragment_len` - maximum number of message bytes per part
+/// * `count` - number of parts to generate
+///
+pub fn qr_encode(
+    message: &MessageTxAPI,
+    max_fragment_len: usize,
+    count: usize,
+) -> Result<Vec<String>, SignerError> {
+    let mut encoder = FountainEncoder::new(&message_to_bytes(message)?, max_fragment_len)?;
+
+    (0..count.max(encoder.seq_len()))
+        .map(|_| encoder.next_part().to_qr_string())
+        .collect()
+}
+
+/// Rebuild a message from scanned QR parts.
+///
+/// # Arguments
+///
+/// * `parts` - the scanned parts, in any order (duplicates are ignored)
+/// * `testnet` - boolean value `true` if testnet or `false` for mainnet
+///
+pub fn qr_decode(parts: &[String], testnet: bool) -> Result<MessageTxAPI, SignerError> {
+    let mut decoder = FountainDecoder::new();
+
+    for part in parts {
+        if decoder.receive(&FountainPart::from_qr_string(part)?)? {
+            break;
+        }
+    }
+
+    message_from_bytes(&decoder.message()?, testnet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> Vec<u8> {
+        (0..1000u32).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn fountain_roundtrip() {
+        let mut encoder = FountainEncoder::new(&message(), 100).unwrap();
+        assert_eq!(encoder.seq_len(), 10);
+
+        // Skip half of the fragments, the fountain parts make up for them
+        let mut decoder = FountainDecoder::new();
+        let mut received = 0;
+        while !decoder.is_complete() {
+            let part = encoder.next_part();
+            if part.seq_num % 2 == 0 && part.seq_num <= 10 {
+                continue;
+            }
+            decoder.receive(&part).unwrap();
+            received += 1;
+            assert!(received < 100);
+        }
+
+        assert_eq!(decoder.progress(), 1.0);
+        assert_eq!(decoder.message().unwrap(), message());
+    }
+
+    #[test]
+    fn fountain_invalid_parts() {
+        let encoder = FountainEncoder::new(&message(), 100).unwrap();
+        let other = FountainEncoder::new(&message()[1..], 100).unwrap();
+
+        let mut decoder = FountainDecoder::new();
+        decoder.receive(&encoder.part(1)).unwrap();
+        assert!(decoder.receive(&other.part(2)).is_err());
+
+        let mut part = encoder.part(2);
+        part.data.pop();
+        assert!(decoder.receive(&part).is_err());
+
+        // Corrupted data is caught by the checksum
+        let mut decoder = FountainDecoder::new();
+        for seq_num in 1..=10 {
+            let mut part = encoder.part(seq_num);
+            if seq_num == 3 {
+                part.data[0] ^= 1;
+            }
+            decoder.receive(&part).unwrap();
+        }
+        assert!(decoder.message().is_err());
+    }
+
+    #[test]
+    fn qr_part_string() {
+        let part = FountainEncoder::new(&message(), 100).unwrap().part(12);
+        let qr = part.to_qr_string().unwrap();
+
+        assert!(qr.starts_with("FILTX/12-10/"));
+        assert!(qr
+            .bytes()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == b'/' || c == b'-'));
+        assert_eq!(FountainPart::from_qr_string(&qr).unwrap(), part);
+
+        assert!(FountainPart::from_qr_string(&qr.replace("12-10", "11-10")).is_err());
+        assert!(FountainPart::from_qr_string("FILTX/1-1").is_err());
+    }
+}
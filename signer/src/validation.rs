@@ -0,0 +1,300 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use forest_address::Protocol;
+use num_bigint_chainsafe::{BigInt, Sign};
+use serde::{Deserialize, Serialize};
+
+use extras::{MethodInit, INIT_ACTOR_ADDR};
+
+use crate::address::{AnyAddress, DELEGATED_PROTOCOL};
+use crate::api::{AnyUnsignedMessage, UnsignedMessageAPI};
+use crate::deserialize_params;
+
+/// Block gas limit, a message can't use more gas than a block
+pub const BLOCK_GAS_LIMIT: i64 = 10_000_000_000;
+
+/// Total Filecoin supply in attoFIL (2 billion FIL)
+const TOTAL_FILECOIN: &str = "2000000000000000000000000000";
+
+/// A problem found in a message field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    /// Name of the message field (e.g `gas_fee_cap`)
+    pub field: String,
+    pub message: String,
+}
+
+/// Findings of `validate_message`. Errors make the node reject the message, warnings point to
+/// messages that are valid but likely a mistake.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub errors: Vec<ValidationIssue>,
+    pub warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// `true` if no error was found (warnings are allowed)
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn error(&mut self, field: &str, message: &str) {
+        self.errors.push(ValidationIssue {
+            field: field.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn warning(&mut self, field: &str, message: &str) {
+        self.warnings.push(ValidationIssue {
+            field: field.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self
+            .errors
+            .iter()
+            .map(|issue| format!("{}: {}", issue.field, issue.message))
+            .collect();
+
+        write!(f, "{}", errors.join("; "))
+    }
+}
+
+fn parse_address(report: &mut ValidationReport, field: &str, address: &str) -> Option<AnyAddress> {
+    match AnyAddress::from_str(address) {
+        Ok(address) => Some(address),
+        Err(err) => {
+            report.error(field, &format!("invalid address ({})", err));
+            None
+        }
+    }
+}
+
+fn parse_amount(report: &mut ValidationReport, field: &str, amount: &str) -> Option<BigInt> {
+    if amount.starts_with('-') {
+        report.error(field, "must not be negative");
+        return None;
+    }
+
+    if amount.is_empty() || !amount.bytes().all(|c| c.is_ascii_digit()) {
+        report.error(field, "must be an integer amount in attoFIL");
+        return None;
+    }
+
+    BigInt::from_str(amount).ok()
+}
+
+/// Check an unsigned message before signing it and return the errors (the node would reject the
+/// message) and warnings (likely mistakes) found.
+///
+/// The actor type of the receiver isn't known offline, so params are only decoded for the init
+/// actor `Exec` method. The params of other calls are only checked to be well-formed CBOR.
+///
+/// # Arguments
+///
+/// * `message` - an unsigned filecoin message
+///
+pub fn validate_message(message: &UnsignedMessageAPI) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    // Addresses
+    let to = parse_address(&mut report, "to", &message.to);
+    let from = parse_address(&mut report, "from", &message.from);
+
+    if let Some(from) = &from {
+        let protocol = from.protocol();
+        if protocol != Protocol::Secp256k1 as u8
+            && protocol != Protocol::BLS as u8
+            && protocol != DELEGATED_PROTOCOL
+        {
+            report.error(
+                "from",
+                "must be a secp256k1, BLS or delegated address to be signed",
+            );
+        }
+    }
+
+    if let (Some(to), Some(from)) = (&to, &from) {
+        if to.network() != from.network() {
+            report.error("to", "`to` and `from` are not on the same network");
+        }
+    }
+
+    // Amounts
+    let value = parse_amount(&mut report, "value", &message.value);
+    let gas_fee_cap = parse_amount(&mut report, "gas_fee_cap", &message.gas_fee_cap);
+    let gas_premium = parse_amount(&mut report, "gas_premium", &message.gas_premium);
+
+    if let Some(value) = &value {
+        if BigInt::from_str(TOTAL_FILECOIN).map_or(false, |total| *value > total) {
+            report.error("value", "greater than the total filecoin supply");
+        }
+        if value.sign() == Sign::NoSign && message.method == 0 {
+            report.warning("value", "transfer of 0 FIL");
+        }
+    }
+
+    if let (Some(gas_fee_cap), Some(gas_premium)) = (&gas_fee_cap, &gas_premium) {
+        if gas_fee_cap < gas_premium {
+            report.error("gas_fee_cap", "`gas_fee_cap` is lower than `gas_premium`");
+        }
+    }
+
+    if let Some(gas_fee_cap) = &gas_fee_cap {
+        if gas_fee_cap.sign() == Sign::NoSign {
+            report.warning(
+                "gas_fee_cap",
+                "is 0, the message won't be included while the base fee is positive",
+            );
+        }
+    }
+
+    // Gas limit
+    if message.gas_limit < 0 {
+        report.error("gas_limit", "must not be negative");
+    } else if message.gas_limit == 0 {
+        report.warning("gas_limit", "is 0, estimate the gas before signing");
+    } else if message.gas_limit > BLOCK_GAS_LIMIT {
+        report.error("gas_limit", "greater than the block gas limit");
+    }
+
+    // Params
+    let to_init_actor = to.as_ref().map_or(false, |to| {
+        to.protocol() == Protocol::ID as u8 && to.payload_bytes() == INIT_ACTOR_ADDR.payload_bytes()
+    });
+
+    match base64::decode(&message.params) {
+        Ok(params) if !params.is_empty() => {
+            if serde_cbor::from_slice::<serde_cbor::Value>(&params).is_err() {
+                report.error("params", "malformed CBOR");
+            } else if message.method == 0 {
+                report.warning("params", "ignored by a plain transfer (method 0)");
+            } else if to_init_actor
+                && message.method == MethodInit::Exec as u64
+                && deserialize_params(
+                    message.params.clone(),
+                    "fil/5/init".to_string(),
+                    message.method,
+                )
+                .is_err()
+            {
+                report.error("params", "not valid init actor `Exec` params");
+            }
+        }
+        Ok(_) => {}
+        Err(_) => report.error("params", "not valid base64"),
+    }
+
+    // Anything the checks above missed would fail the serialization
+    if report.is_valid() {
+        if let Err(err) = AnyUnsignedMessage::try_from(message) {
+            report.error("message", &format!("cannot be serialized ({})", err));
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> UnsignedMessageAPI {
+        UnsignedMessageAPI {
+            to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+            from: "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba".to_string(),
+            nonce: 1,
+            value: "100000".to_string(),
+            gas_limit: 25000,
+            gas_fee_cap: "2500".to_string(),
+            gas_premium: "2500".to_string(),
+            method: 0,
+            params: "".to_string(),
+        }
+    }
+
+    fn fields(issues: &[ValidationIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.field.as_str()).collect()
+    }
+
+    #[test]
+    fn valid_message() {
+        let report = validate_message(&message());
+
+        assert!(report.is_valid());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn invalid_message() {
+        let mut invalid = message();
+        invalid.to = "f17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string();
+        invalid.value = "-1".to_string();
+        invalid.gas_fee_cap = "100".to_string();
+        invalid.gas_limit = BLOCK_GAS_LIMIT + 1;
+        invalid.params = "not base64!".to_string();
+
+        let report = validate_message(&invalid);
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            fields(&report.errors),
+            vec!["to", "value", "gas_fee_cap", "gas_limit", "params"]
+        );
+    }
+
+    #[test]
+    fn message_warnings() {
+        let mut unusual = message();
+        unusual.value = "0".to_string();
+        unusual.gas_fee_cap = "0".to_string();
+        unusual.gas_premium = "0".to_string();
+        unusual.gas_limit = 0;
+        unusual.params = base64::encode(&[0x80]);
+
+        let report = validate_message(&unusual);
+
+        assert!(report.is_valid());
+        assert_eq!(
+            fields(&report.warnings),
+            vec!["value", "gas_fee_cap", "gas_limit", "params"]
+        );
+
+        unusual.params = base64::encode(&[0x82, 0x01]);
+        assert_eq!(fields(&validate_message(&unusual).errors), vec!["params"]);
+
+        unusual.from = "t01234".to_string();
+        assert_eq!(
+            fields(&validate_message(&unusual).errors),
+            vec!["from", "params"]
+        );
+    }
+
+    #[test]
+    fn init_exec_params() {
+        let mut exec = message();
+        exec.to = "t01".to_string();
+        exec.method = MethodInit::Exec as u64;
+        exec.value = "0".to_string();
+
+        // Well-formed CBOR but not `ExecParams`
+        exec.params = base64::encode(&[0x82, 0x01, 0x02]);
+        assert_eq!(fields(&validate_message(&exec).errors), vec!["params"]);
+
+        // `ExecParams` of a multisig creation
+        exec.params = "gtgqUwABVQAOZmlsLzUvbXVsdGlzaWdA".to_string();
+        assert!(validate_message(&exec).is_valid());
+
+        // Other receivers are only checked to be well-formed CBOR
+        exec.to = "t01234".to_string();
+        exec.params = base64::encode(&[0x82, 0x01, 0x02]);
+        assert!(validate_message(&exec).is_valid());
+    }
+}
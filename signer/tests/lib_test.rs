@@ -4,16 +4,22 @@ use bip39::{Language, Seed};
 use bls_signatures::Serialize;
 use forest_address::Address;
 use forest_encoding::{to_vec, Cbor};
-use forest_message::UnsignedMessage;
+use forest_message::{SignedMessage, UnsignedMessage};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
 
-use filecoin_signer::api::{MessageParams, MessageTxAPI, UnsignedMessageAPI};
-use filecoin_signer::signature::{Signature, SignatureBLS};
+use filecoin_signer::api::{
+    MessageParams, MessageReturn, MessageTxAPI, ProposeReturnMultisig, SigTypes, SignedMessageAPI,
+    UnsignedMessageAPI,
+};
+use filecoin_signer::error::SignerError;
+use filecoin_signer::keystore::KeystoreStorage;
+use filecoin_signer::signature::{Signature, SignatureBLS, SignatureSECP256K1};
+use filecoin_signer::signer::{KeyType, Signer};
 use filecoin_signer::*;
 
-use extras::multisig;
+use extras::{multisig, MethodInit};
 
 mod common;
 
@@ -38,6 +44,83 @@ fn generate_mnemonic() {
     assert_eq!(word_count, 24)
 }
 
+#[test]
+fn generate_mnemonic_with_options() {
+    let mnemonic = key_generate_mnemonic_with_options(12, "en", None).unwrap();
+    assert_eq!(mnemonic.0.split_ascii_whitespace().count(), 12);
+    assert!(mnemonic_validate(&mnemonic.0, "en").is_ok());
+
+    let mnemonic = key_generate_mnemonic_with_options(18, "en", None).unwrap();
+    assert_eq!(mnemonic.0.split_ascii_whitespace().count(), 18);
+
+    assert!(matches!(
+        key_generate_mnemonic_with_options(13, "en", None),
+        Err(SignerError::InvalidMnemonicWordCount(13))
+    ));
+    assert!(key_generate_mnemonic_with_options(12, "xx", None).is_err());
+}
+
+#[test]
+fn generate_mnemonic_from_entropy() {
+    let mnemonic = key_generate_mnemonic_with_options(12, "en", Some(&[0x7f; 16][..])).unwrap();
+    assert_eq!(
+        mnemonic.0,
+        "legal winner thank year wave sausage worth useful legal winner thank yellow"
+    );
+
+    let mnemonic = key_generate_mnemonic_with_options(24, "en", Some(&[0u8; 32][..])).unwrap();
+    assert_eq!(
+        mnemonic.0,
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art"
+    );
+
+    assert!(matches!(
+        key_generate_mnemonic_with_options(24, "en", Some(&[0u8; 16][..])),
+        Err(SignerError::InvalidEntropyLength(128))
+    ));
+}
+
+#[test]
+fn validate_mnemonic_errors() {
+    assert!(mnemonic_validate(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "en"
+    )
+    .is_ok());
+
+    assert!(matches!(
+        mnemonic_validate(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+            "en"
+        ),
+        Err(SignerError::InvalidMnemonicChecksum)
+    ));
+
+    match mnemonic_validate(
+        "abandon abandon abandon abandon abandon filecoin abandon abandon abandon abandon abandon about",
+        "en",
+    ) {
+        Err(SignerError::UnknownMnemonicWord(word)) => assert_eq!(word, "filecoin"),
+        _ => panic!("Should be an unknown word error"),
+    }
+
+    assert!(matches!(
+        mnemonic_validate("abandon abandon abandon", "en"),
+        Err(SignerError::InvalidMnemonicWordCount(3))
+    ));
+
+    // key derivation reports the same errors
+    assert!(matches!(
+        key_derive(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+            "m/44'/461'/0/0/0",
+            "",
+            "en"
+        ),
+        Err(SignerError::InvalidMnemonicChecksum)
+    ));
+}
+
 #[test]
 fn derive_key() {
     let test_value = common::load_test_vectors("../test_vectors/wallet.json").unwrap();
@@ -93,6 +176,130 @@ fn derive_key_from_seed() {
     );
 }
 
+#[test]
+fn derive_address_from_xpub() {
+    let mnemonic = "equip will roof matter pink blind book anxiety banner elbow sun young";
+
+    let xpub = key_derive_xpub(mnemonic, "m/44'/461'/0", "", "en").unwrap();
+    assert!(xpub.starts_with("xpub"));
+
+    for index in 0..3 {
+        let extended_key =
+            key_derive(mnemonic, &format!("m/44'/461'/0/0/{}", index), "", "en").unwrap();
+        let address = address_derive_from_xpub(&xpub, &format!("0/{}", index), false).unwrap();
+
+        assert_eq!(address, extended_key.address);
+    }
+
+    assert!(address_derive_from_xpub(&xpub, "0'/1", false).is_err());
+}
+
+#[test]
+fn derive_key_batch() {
+    let mnemonic = "equip will roof matter pink blind book anxiety banner elbow sun young";
+
+    let keys = key_derive_batch(mnemonic, "m/44'/1'/0/0/{i}", "", "en", 2, 5).unwrap();
+    assert_eq!(keys.len(), 5);
+
+    for (offset, key) in keys.iter().enumerate() {
+        let expected =
+            key_derive(mnemonic, &format!("m/44'/1'/0/0/{}", offset + 2), "", "en").unwrap();
+
+        assert_eq!(key.address, expected.address);
+        assert_eq!(key.private_key.0, expected.private_key.0);
+        assert!(key.address.starts_with('t'));
+    }
+
+    assert!(key_derive_batch(mnemonic, "m/44'/461'/0/0/0", "", "en", 0, 5).is_err());
+}
+
+#[test]
+fn scan_gap_limit() {
+    let mnemonic = "equip will roof matter pink blind book anxiety banner elbow sun young";
+    let template = "m/44'/461'/0'/0/{i}";
+
+    let keys = key_derive_batch(mnemonic, template, "", "en", 0, 12).unwrap();
+    let used: Vec<String> = vec![
+        keys[0].address.clone(),
+        keys[4].address.clone(),
+        keys[11].address.clone(),
+    ];
+
+    let mut checked = 0;
+    let found = key_scan_gap_limit(mnemonic, template, "", "en", 5, |address| {
+        checked += 1;
+        used.contains(&address.to_string())
+    })
+    .unwrap();
+
+    // index 11 is after a gap of 6 unused addresses
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].address, keys[0].address);
+    assert_eq!(found[1].address, keys[4].address);
+    assert_eq!(checked, 10);
+
+    let found = key_scan_gap_limit(mnemonic, template, "", "en", 7, |address| {
+        used.contains(&address.to_string())
+    })
+    .unwrap();
+    assert_eq!(found.len(), 3);
+}
+
+#[test]
+fn derive_key_bls() {
+    let test_value = common::load_test_vectors("../test_vectors/wallet.json").unwrap();
+    let mnemonic = test_value["mnemonic"].as_str().unwrap();
+    let language_code = test_value["language_code"].as_str().unwrap();
+
+    let extended_key = key_derive_bls(&mnemonic, "m/12381/461/0/0", "", language_code).unwrap();
+
+    assert_eq!(
+        base64::encode(&extended_key.private_key.0),
+        "v1kx/HyEiz7BmpqNz1DrwARk1woscy8b2l54HtzruG8="
+    );
+    assert!(extended_key.address.starts_with("f3"));
+
+    // The derived key must be usable as a regular BLS key
+    let recovered_key = key_recover_bls(&extended_key.private_key, false).unwrap();
+    assert_eq!(recovered_key.address, extended_key.address);
+    assert_eq!(
+        recovered_key.public_key.to_vec(),
+        extended_key.public_key.to_vec()
+    );
+}
+
+#[test]
+fn derive_key_bls_testnet_from_seed() {
+    let test_value = common::load_test_vectors("../test_vectors/wallet.json").unwrap();
+    let mnemonic = test_value["mnemonic"].as_str().unwrap();
+
+    let m = bip39::Mnemonic::from_phrase(&mnemonic, Language::English).unwrap();
+    let seed = Seed::new(&m, "");
+
+    let extended_key = key_derive_bls_from_seed(seed.as_bytes(), "m/12381/1/0/0").unwrap();
+
+    assert_eq!(
+        base64::encode(&extended_key.private_key.0),
+        "XEE/hAfei9sZnBol1imuImgwTdw+VlSlcMSAxWuTVF0="
+    );
+    assert!(extended_key.address.starts_with("t3"));
+}
+
+#[test]
+fn generate_bls_key() {
+    let extended_key = key_generate_bls(true).unwrap();
+    let other_extended_key = key_generate_bls(true).unwrap();
+
+    assert!(extended_key.address.starts_with("t3"));
+    assert_ne!(extended_key.private_key.0, other_extended_key.private_key.0);
+
+    let recovered_key = key_recover_bls(&extended_key.private_key, true).unwrap();
+    assert_eq!(recovered_key.address, extended_key.address);
+
+    let mainnet_key = key_generate_bls(false).unwrap();
+    assert!(mainnet_key.address.starts_with("f3"));
+}
+
 #[test]
 fn test_key_recover_testnet() {
     let test_value = common::load_test_vectors("../test_vectors/wallet.json").unwrap();
@@ -133,6 +340,254 @@ fn test_key_recover_mainnet() {
     assert_eq!(&recovered_key.address, &address);
 }
 
+#[test]
+fn secrets_are_redacted() {
+    let mnemonic = key_generate_mnemonic().unwrap();
+    let extended_key = key_derive(mnemonic.export_phrase(), "m/44'/461'/0/0/0", "", "en").unwrap();
+    let private_key_hex = extended_key.private_key.export_hex();
+
+    let debug_output = format!("{:?}", extended_key);
+    assert!(debug_output.contains(&extended_key.address));
+    assert!(!debug_output.contains(&private_key_hex));
+
+    assert_eq!(format!("{}", extended_key.private_key), "<redacted>");
+    assert_eq!(format!("{}", mnemonic), "<redacted>");
+    assert!(!format!("{:?}", mnemonic).contains(mnemonic.export_phrase()));
+
+    assert_eq!(
+        extended_key.private_key.export_bytes(),
+        &hex::decode(&private_key_hex).unwrap()[..]
+    );
+    assert_eq!(
+        extended_key.private_key.export_base64(),
+        base64::encode(&extended_key.private_key.0)
+    );
+}
+
+#[test]
+fn import_lotus_key() {
+    let test_value = common::load_test_vectors("../test_vectors/wallet.json").unwrap();
+    let private_key = test_value["private_key"].as_str().unwrap();
+
+    // Output of `lotus wallet export`
+    let exported_key = hex::encode(format!(
+        "{{\"Type\":\"secp256k1\",\"PrivateKey\":\"{}\"}}",
+        private_key
+    ));
+
+    let imported_key = key_import_lotus(&exported_key, true).unwrap();
+
+    assert_eq!(
+        base64::encode(&imported_key.private_key.0),
+        private_key.to_string()
+    );
+    assert_eq!(
+        &imported_key.address,
+        "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba"
+    );
+
+    assert_eq!(key_export_lotus(&imported_key).unwrap(), exported_key);
+}
+
+#[test]
+fn export_import_lotus_bls_key() {
+    let test_value = common::load_test_vectors("../test_vectors/wallet.json").unwrap();
+    let bls_key =
+        PrivateKey::try_from(test_value["bls_private_key"].as_str().unwrap().to_string()).unwrap();
+
+    let extended_key = key_recover_bls(&bls_key, true).unwrap();
+    let exported_key = key_export_lotus(&extended_key).unwrap();
+
+    let key_info: serde_json::Value =
+        serde_json::from_slice(&hex::decode(&exported_key).unwrap()).unwrap();
+    assert_eq!(key_info["Type"], "bls");
+
+    let imported_key = key_import_lotus(&exported_key, true).unwrap();
+    assert_eq!(imported_key.address, extended_key.address);
+    assert_eq!(imported_key.private_key.0, extended_key.private_key.0);
+
+    let invalid_key = hex::encode("{\"Type\":\"unknown\",\"PrivateKey\":\"\"}");
+    assert!(key_import_lotus(&invalid_key, true).is_err());
+}
+
+#[test]
+fn lotus_keystore() {
+    let keystore_path = std::env::temp_dir().join(format!("lotus-keystore-{}", std::process::id()));
+    std::fs::create_dir_all(&keystore_path).unwrap();
+
+    let secp256k1_key = key_recover(&PrivateKey([1u8; 32]), false).unwrap();
+    let bls_key = key_generate_bls(false).unwrap();
+
+    lotus::keystore_write(&keystore_path, &secp256k1_key).unwrap();
+    lotus::keystore_write(&keystore_path, &bls_key).unwrap();
+    assert!(lotus::keystore_write(&keystore_path, &bls_key).is_err());
+
+    let mut expected_addresses = vec![secp256k1_key.address.clone(), bls_key.address.clone()];
+    expected_addresses.sort();
+    assert_eq!(
+        lotus::keystore_list(&keystore_path).unwrap(),
+        expected_addresses
+    );
+
+    let read_key = lotus::keystore_read(&keystore_path, &bls_key.address).unwrap();
+    assert_eq!(read_key.private_key.0, bls_key.private_key.0);
+
+    std::fs::remove_dir_all(&keystore_path).unwrap();
+}
+
+fn test_keystore() -> keystore::Keystore<keystore::MemoryStorage> {
+    // Low cost parameters to keep tests fast
+    let kdf_params = keystore::KdfParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    keystore::Keystore::with_kdf_params(keystore::MemoryStorage::default(), kdf_params)
+}
+
+#[test]
+fn keystore_create_unlock() {
+    let mnemonic = key_generate_mnemonic().unwrap();
+    let extended_key = key_derive(&mnemonic.0, "m/44'/1'/0/0/0", "", "en").unwrap();
+    let bls_key = key_generate_bls(false).unwrap();
+
+    let mut keystore = test_keystore();
+    keystore
+        .create(&extended_key, Some(&mnemonic.0), "password")
+        .unwrap();
+    keystore.create(&bls_key, None, "password").unwrap();
+
+    assert!(keystore.create(&bls_key, None, "password").is_err());
+
+    let mut expected_addresses = vec![extended_key.address.clone(), bls_key.address.clone()];
+    expected_addresses.sort();
+    assert_eq!(keystore.list().unwrap(), expected_addresses);
+
+    let unlocked = keystore.unlock(&extended_key.address, "password").unwrap();
+    assert_eq!(unlocked.extended_key.address, extended_key.address);
+    assert_eq!(
+        unlocked.extended_key.private_key.0,
+        extended_key.private_key.0
+    );
+    assert_eq!(
+        unlocked.mnemonic.as_ref().map(Mnemonic::export_phrase),
+        Some(mnemonic.export_phrase())
+    );
+
+    let unlocked_bls = keystore.unlock(&bls_key.address, "password").unwrap();
+    assert_eq!(
+        unlocked_bls.extended_key.private_key.0,
+        bls_key.private_key.0
+    );
+    assert!(unlocked_bls.mnemonic.is_none());
+
+    match keystore.unlock(&extended_key.address, "wrong") {
+        Err(SignerError::InvalidPassword) => {}
+        _ => panic!("expected invalid password error"),
+    }
+    assert!(keystore.unlock("t1unknown", "password").is_err());
+}
+
+#[test]
+fn keystore_change_password() {
+    let extended_key = key_recover(&PrivateKey([1u8; 32]), true).unwrap();
+
+    let mut keystore = test_keystore();
+    keystore.create(&extended_key, None, "old").unwrap();
+
+    assert!(keystore
+        .change_password(&extended_key.address, "wrong", "new")
+        .is_err());
+    keystore
+        .change_password(&extended_key.address, "old", "new")
+        .unwrap();
+
+    assert!(keystore.unlock(&extended_key.address, "old").is_err());
+    let unlocked = keystore.unlock(&extended_key.address, "new").unwrap();
+    assert_eq!(unlocked.extended_key.private_key.0, [1u8; 32]);
+
+    // Entry is versioned JSON and doesn't leak the private key
+    let entry = keystore
+        .storage()
+        .load(&extended_key.address)
+        .unwrap()
+        .unwrap();
+    let entry: serde_json::Value = serde_json::from_str(&entry).unwrap();
+    assert_eq!(entry["version"], 1);
+    assert_eq!(entry["crypto"]["kdf"], "scrypt");
+    assert!(!entry
+        .to_string()
+        .contains(&base64::encode(&extended_key.private_key.0)));
+}
+
+#[test]
+fn keystore_rejects_untrusted_entries() {
+    let kdf_params = keystore::KdfParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+    let extended_key = key_recover(&PrivateKey([1u8; 32]), true).unwrap();
+    let other_key = key_recover(&PrivateKey([2u8; 32]), true).unwrap();
+
+    let mut entry =
+        keystore::KeystoreEntry::encrypt(&extended_key, None, "password", kdf_params).unwrap();
+
+    // Entry of another key stored under an address
+    let mut storage = keystore::MemoryStorage::default();
+    storage
+        .store(&other_key.address, &serde_json::to_string(&entry).unwrap())
+        .unwrap();
+    let keystore = keystore::Keystore::with_kdf_params(storage, kdf_params);
+    assert!(keystore.unlock(&other_key.address, "password").is_err());
+
+    // Scrypt parameters are capped
+    entry.crypto.kdf_params.log_n = 40;
+    assert!(entry.decrypt("password").is_err());
+    entry.crypto.kdf_params = keystore::KdfParams {
+        log_n: 20,
+        r: 32,
+        p: 1,
+    };
+    assert!(entry.decrypt("password").is_err());
+}
+
+#[test]
+fn keystore_directory_storage() {
+    let keystore_path = std::env::temp_dir().join(format!("keystore-{}", std::process::id()));
+    let extended_key = key_recover(&PrivateKey([1u8; 32]), true).unwrap();
+
+    let kdf_params = keystore::KdfParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+    let mut keystore = keystore::Keystore::with_kdf_params(
+        keystore::DirectoryStorage::new(&keystore_path),
+        kdf_params,
+    );
+    keystore.create(&extended_key, None, "password").unwrap();
+
+    assert_eq!(keystore.list().unwrap(), vec![extended_key.address.clone()]);
+    assert!(keystore.unlock(&extended_key.address, "password").is_ok());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let entry_path = keystore_path.join(format!("{}.json", extended_key.address));
+        let mode = std::fs::metadata(entry_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // Only addresses are accepted as entry names
+    assert!(keystore.storage().load("../../etc/passwd").is_err());
+    assert!(keystore.unlock("../keystore/x", "password").is_err());
+
+    std::fs::remove_dir_all(&keystore_path).unwrap();
+}
+
 #[test]
 fn parse_unsigned_transaction() {
     let test_value = common::load_test_vectors("../test_vectors/txs.json").unwrap();
@@ -167,6 +622,68 @@ fn parse_signed_transaction() {
     );
 }
 
+#[test]
+fn signed_message_serialize_roundtrip() {
+    let cbor_data = CborBuffer(hex::decode(SIGNED_MESSAGE_CBOR).unwrap());
+
+    let signed_message = signed_message_parse(&cbor_data, true).unwrap();
+    assert_eq!(
+        signed_message.signature.sig_type,
+        SigTypes::SigTypeSecp256k1 as u8
+    );
+    assert_eq!(
+        signed_message_serialize(&signed_message).unwrap().0,
+        cbor_data.0
+    );
+
+    // An unsigned message isn't a signed message
+    let unsigned_cbor = transaction_serialize(&signed_message.message).unwrap();
+    assert!(signed_message_parse(&unsigned_cbor, true).is_err());
+
+    // Secp256k1: the CID is the hash of the signed message
+    let message_tx = MessageTxAPI::SignedMessageAPI(signed_message.clone());
+    let cid = message_cid(&message_tx).unwrap();
+    assert_eq!(cid.to_string(), get_cid(message_tx).unwrap());
+    assert_ne!(
+        cid,
+        message_cid(&MessageTxAPI::UnsignedMessageAPI(
+            signed_message.message.clone()
+        ))
+        .unwrap()
+    );
+
+    // BLS: the CID is the CID of the unsigned message
+    let bls_key = key_generate_bls(true).unwrap();
+    let message = UnsignedMessageAPI {
+        from: bls_key.address.clone(),
+        ..signed_message.message
+    };
+    let bls_signed_message = transaction_sign(&message, &bls_key.private_key).unwrap();
+
+    let cbor = signed_message_serialize(&bls_signed_message).unwrap();
+    let parsed = signed_message_parse(&cbor, true).unwrap();
+    assert_eq!(parsed, bls_signed_message);
+    assert_eq!(parsed.signature.sig_type, SigTypes::SigTypeBLS as u8);
+
+    let forest_signed_message = SignedMessage::try_from(&bls_signed_message).unwrap();
+    assert_eq!(
+        SignedMessageAPI::from(forest_signed_message)
+            .signature
+            .sig_type,
+        SigTypes::SigTypeBLS as u8
+    );
+
+    let unsigned_cid = message_cid(&MessageTxAPI::UnsignedMessageAPI(message)).unwrap();
+    assert_eq!(
+        message_cid(&MessageTxAPI::SignedMessageAPI(bls_signed_message.clone())).unwrap(),
+        unsigned_cid
+    );
+    assert_eq!(
+        get_cid_bytes(&MessageTxAPI::SignedMessageAPI(bls_signed_message)).unwrap(),
+        unsigned_cid.to_bytes()
+    );
+}
+
 #[test]
 fn parse_transaction_with_network() {
     let test_value = common::load_test_vectors("../test_vectors/txs.json").unwrap();
@@ -246,6 +763,502 @@ fn parse_transaction_signed_with_network_testnet() {
     );
 }
 
+/// External signer only able to sign digests (e.g an HSM)
+struct DigestSigner {
+    key: ExtendedKey,
+}
+
+impl Signer for DigestSigner {
+    fn address(&self) -> &str {
+        &self.key.address
+    }
+
+    fn key_type(&self) -> KeyType {
+        KeyType::Secp256k1
+    }
+
+    fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature, SignerError> {
+        self.key.sign_digest(digest)
+    }
+}
+
+#[test]
+fn sign_with_signer() {
+    let test_value = common::load_test_vectors("../test_vectors/wallet.json").unwrap();
+    let private_key = test_value["private_key"].as_str().unwrap();
+    let pk = PrivateKey::try_from(private_key.to_string()).unwrap();
+
+    let signer = DigestSigner {
+        key: key_recover(&pk, true).unwrap(),
+    };
+
+    let message = UnsignedMessageAPI {
+        to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+        from: signer.address().to_string(),
+        nonce: 1,
+        value: "100000".to_string(),
+        gas_limit: 25000,
+        gas_fee_cap: "2500".to_string(),
+        gas_premium: "2500".to_string(),
+        method: 0,
+        params: "".to_string(),
+    };
+
+    let signature = transaction_sign_raw_with_signer(&message, &signer).unwrap();
+    assert_eq!(
+        signature.as_bytes(),
+        transaction_sign_raw(&message, &pk).unwrap().as_bytes()
+    );
+
+    let message_cbor = transaction_serialize(&message).unwrap();
+    assert!(verify_signature(&signature, &message_cbor).unwrap());
+
+    // The network of the `from` address doesn't matter
+    let mainnet_message = UnsignedMessageAPI {
+        from: address::address_to_network(&message.from, false).unwrap(),
+        ..message.clone()
+    };
+    assert!(transaction_sign_raw_with_signer(&mainnet_message, &signer).is_ok());
+
+    // The signer must hold the key of the `from` address
+    let other_signer = DigestSigner {
+        key: key_recover(&PrivateKey([1u8; 32]), true).unwrap(),
+    };
+    assert!(transaction_sign_raw_with_signer(&message, &other_signer).is_err());
+
+    // A BLS signer can't sign for a secp256k1 address
+    let bls_signer = key_generate_bls(true).unwrap();
+    assert!(transaction_sign_raw_with_signer(&message, &bls_signer).is_err());
+
+    let bls_message = UnsignedMessageAPI {
+        from: bls_signer.address.clone(),
+        ..message
+    };
+    let signed_message = transaction_sign_with_signer(&bls_message, &bls_signer).unwrap();
+    assert_eq!(signed_message.signature.sig_type, 2);
+}
+
+#[test]
+fn sign_verify_message() {
+    let challenge = b"login challenge 1234";
+    let prefix = format!("\x19Filecoin Signed Message:\n{}", challenge.len());
+    let prefix = Some(prefix.as_bytes());
+
+    let secp256k1_key = key_recover(&PrivateKey([1u8; 32]), false).unwrap();
+    let bls_key = key_generate_bls(true).unwrap();
+
+    for key in &[secp256k1_key, bls_key] {
+        let signature = sign_message(challenge, prefix, key).unwrap();
+
+        assert!(verify_message(&signature, challenge, prefix, &key.address).unwrap());
+        // The network of the address doesn't matter
+        let network = if key.address.starts_with('f') {
+            "t"
+        } else {
+            "f"
+        };
+        let other_network = format!("{}{}", network, &key.address[1..]);
+        assert!(verify_message(&signature, challenge, prefix, &other_network).unwrap());
+
+        assert!(!verify_message(&signature, challenge, None, &key.address).unwrap_or(false));
+        assert!(
+            !verify_message(&signature, b"other challenge", prefix, &key.address).unwrap_or(false)
+        );
+        assert!(!verify_message(
+            &signature,
+            challenge,
+            prefix,
+            "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba"
+        )
+        .unwrap());
+    }
+}
+
+#[test]
+fn sign_message_lotus_raw() {
+    let key = key_recover(&PrivateKey([1u8; 32]), true).unwrap();
+    let message = b"raw bytes";
+
+    // Lotus signs the blake2b-256 hash of the raw bytes
+    let signature = sign_message(message, None, &key).unwrap();
+    let expected = key
+        .sign_digest(&forest_encoding::blake2b_256(message))
+        .unwrap();
+
+    assert_eq!(signature.as_bytes(), expected.as_bytes());
+}
+
+#[test]
+fn recover_signer_address() {
+    let key = key_recover(&PrivateKey([1u8; 32]), true).unwrap();
+    let public_key = match &key.public_key {
+        PublicKey::PublicKeySECP256K1(public_key) => public_key.0,
+        _ => panic!("Expected a secp256k1 key"),
+    };
+
+    // Transaction
+    let message = UnsignedMessageAPI {
+        to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+        from: key.address.clone(),
+        nonce: 1,
+        value: "100000".to_string(),
+        gas_limit: 25000,
+        gas_fee_cap: "2500".to_string(),
+        gas_premium: "2500".to_string(),
+        method: 0,
+        params: "".to_string(),
+    };
+    let message_cbor = transaction_serialize(&message).unwrap();
+    let signature = match transaction_sign_raw(&message, &PrivateKey([1u8; 32])).unwrap() {
+        Signature::SignatureSECP256K1(signature) => signature,
+        _ => panic!("Expected a secp256k1 signature"),
+    };
+
+    // Transactions are signed over the digest of the message CID
+    let digest = utils::get_digest(message_cbor.as_ref()).unwrap();
+    assert_eq!(
+        key.sign_digest(&digest).unwrap().as_bytes(),
+        signature.0.to_vec()
+    );
+
+    let recovered =
+        recover_signer(&signature, SignedPayload::Transaction(&message_cbor), true).unwrap();
+    assert_eq!(recovered.address, key.address);
+    assert_eq!(recovered.public_key.0.to_vec(), public_key.to_vec());
+
+    let recovered =
+        recover_signer(&signature, SignedPayload::Transaction(&message_cbor), false).unwrap();
+    assert_eq!(recovered.address, format!("f{}", &key.address[1..]));
+
+    // A signature over another payload recovers another address
+    let wrong_signature = match key.sign_bytes(&message_cbor.0).unwrap() {
+        Signature::SignatureSECP256K1(signature) => signature,
+        _ => panic!("Expected a secp256k1 signature"),
+    };
+    let recovered = recover_signer(
+        &wrong_signature,
+        SignedPayload::Transaction(&message_cbor),
+        true,
+    )
+    .unwrap();
+    assert_ne!(recovered.address, key.address);
+
+    // Raw digest
+    let digest = forest_encoding::blake2b_256(b"raw bytes");
+    let signature = match key.sign_digest(&digest).unwrap() {
+        Signature::SignatureSECP256K1(signature) => signature,
+        _ => panic!("Expected a secp256k1 signature"),
+    };
+    let recovered = recover_signer(&signature, SignedPayload::Digest(&digest), true).unwrap();
+    assert_eq!(recovered.address, key.address);
+
+    // Voucher
+    let voucher = create_voucher("t01003".to_string(), 0, 0, "1000".to_string(), 0, 1, 0).unwrap();
+    let signed_voucher = sign_voucher(voucher, &PrivateKey([1u8; 32])).unwrap();
+    let voucher_bytes = base64::decode(&signed_voucher).unwrap();
+    let voucher: extras::paych::SignedVoucher =
+        forest_encoding::from_slice(&voucher_bytes).unwrap();
+    let signature =
+        SignatureSECP256K1::try_from(voucher.signature.unwrap().bytes().to_vec()).unwrap();
+    let recovered =
+        recover_signer(&signature, SignedPayload::Voucher(&voucher_bytes), true).unwrap();
+    assert_eq!(recovered.address, key.address);
+}
+
+#[test]
+fn verify_mainnet_signature() {
+    let secp256k1_key = key_recover(&PrivateKey([1u8; 32]), false).unwrap();
+    let bls_key = key_generate_bls(false).unwrap();
+
+    for key in &[secp256k1_key, bls_key] {
+        assert!(key.address.starts_with('f'));
+
+        let message = UnsignedMessageAPI {
+            to: "f17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+            from: key.address.clone(),
+            nonce: 1,
+            value: "100000".to_string(),
+            gas_limit: 25000,
+            gas_fee_cap: "2500".to_string(),
+            gas_premium: "2500".to_string(),
+            method: 0,
+            params: "".to_string(),
+        };
+
+        let signature = transaction_sign_raw_with_signer(&message, key).unwrap();
+        let message_cbor = transaction_serialize(&message).unwrap();
+
+        assert!(verify_signature(&signature, &message_cbor).unwrap());
+    }
+}
+
+#[test]
+fn verify_signed_message_type_and_protocol() {
+    let secp256k1_key = key_recover(&PrivateKey([1u8; 32]), false).unwrap();
+    let bls_key = key_generate_bls(true).unwrap();
+
+    for key in &[secp256k1_key, bls_key] {
+        let message = UnsignedMessageAPI {
+            to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+            from: key.address.clone(),
+            nonce: 1,
+            value: "100000".to_string(),
+            gas_limit: 25000,
+            gas_fee_cap: "2500".to_string(),
+            gas_premium: "2500".to_string(),
+            method: 0,
+            params: "".to_string(),
+        };
+
+        let signed_message = transaction_sign_with_signer(&message, key).unwrap();
+        assert!(verify_signed_message(&signed_message).unwrap());
+
+        // Tampered message
+        let mut tampered = signed_message.clone();
+        tampered.message.nonce = 2;
+        assert!(!verify_signed_message(&tampered).unwrap());
+
+        // Signature type not matching the `from` protocol
+        let mut wrong_type = signed_message.clone();
+        wrong_type.signature.sig_type = 3 - wrong_type.signature.sig_type;
+        assert!(!verify_signed_message(&wrong_type).unwrap_or(false));
+
+        // Unknown signature type
+        let mut unknown_type = signed_message;
+        unknown_type.signature.sig_type = 9;
+        assert!(verify_signed_message(&unknown_type).is_err());
+    }
+}
+
+#[test]
+fn sign_verify_batch() {
+    let private_key = PrivateKey([1u8; 32]);
+    let key = key_recover(&private_key, true).unwrap();
+
+    let mut messages: Vec<UnsignedMessageAPI> = (0..20)
+        .map(|nonce| UnsignedMessageAPI {
+            to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+            from: key.address.clone(),
+            nonce,
+            value: "100000".to_string(),
+            gas_limit: 25000,
+            gas_fee_cap: "2500".to_string(),
+            gas_premium: "2500".to_string(),
+            method: 0,
+            params: "".to_string(),
+        })
+        .collect();
+    // Invalid value
+    messages[3].value = "-".to_string();
+
+    let signed_messages = transaction_sign_batch(&messages, &private_key);
+    assert_eq!(signed_messages.len(), messages.len());
+    assert!(signed_messages[3].is_err());
+
+    let signed_messages_with_signer = transaction_sign_batch_with_signer(&messages, &key);
+
+    let mut signed_messages: Vec<SignedMessageAPI> = signed_messages
+        .into_iter()
+        .zip(signed_messages_with_signer.into_iter())
+        .enumerate()
+        .filter(|(index, _)| *index != 3)
+        .map(|(_, (signed_message, signed_message_with_signer))| {
+            let signed_message = signed_message.unwrap();
+            assert_eq!(signed_message, signed_message_with_signer.unwrap());
+            signed_message
+        })
+        .collect();
+    assert_eq!(signed_messages[5].message.nonce, 6);
+
+    // Tamper one message
+    signed_messages[7].message.nonce = 100;
+
+    let results = verify_signed_message_batch(&signed_messages);
+    for (index, result) in results.into_iter().enumerate() {
+        assert_eq!(result.unwrap(), index != 7);
+    }
+
+    let signatures: Vec<(Signature, CborBuffer)> = signed_messages
+        .iter()
+        .map(|signed_message| {
+            (
+                Signature::try_from(signed_message.signature.data.clone()).unwrap(),
+                transaction_serialize(&signed_message.message).unwrap(),
+            )
+        })
+        .collect();
+
+    let results = verify_signature_batch(&signatures);
+    for (index, result) in results.into_iter().enumerate() {
+        assert_eq!(result.unwrap(), index != 7);
+    }
+}
+
+#[test]
+fn delegated_address_support() {
+    let private_key = PrivateKey([1u8; 32]);
+    let key = key_recover(&private_key, true).unwrap();
+
+    let f410_address =
+        address::f410_address_from_public_key(&key.public_key.to_vec(), true).unwrap();
+    assert_eq!(f410_address, "t410fdjsc6dr4hl2ulz5mxu4la4srwomqsfhrj5x3f4i");
+    assert_eq!(
+        address::f410_to_eth_address(&f410_address).unwrap(),
+        "0x1a642f0e3c3af545e7acbd38b07251b3990914f1"
+    );
+    assert_eq!(
+        address::eth_address_to_f410("0x1a642f0e3c3af545e7acbd38b07251b3990914f1", true).unwrap(),
+        f410_address
+    );
+
+    // Transfer to a f410 address
+    let message = UnsignedMessageAPI {
+        to: f410_address.clone(),
+        from: key.address.clone(),
+        nonce: 1,
+        value: "100000".to_string(),
+        gas_limit: 25000,
+        gas_fee_cap: "2500".to_string(),
+        gas_premium: "2500".to_string(),
+        method: 0,
+        params: "".to_string(),
+    };
+
+    let cbor = transaction_serialize(&message).unwrap();
+    match transaction_parse(&cbor, true).unwrap() {
+        MessageTxAPI::UnsignedMessageAPI(parsed) => assert_eq!(parsed, message),
+        MessageTxAPI::SignedMessageAPI(_) => panic!("Should be an unsigned message"),
+    }
+
+    let signed_message = transaction_sign(&message, &private_key).unwrap();
+    assert!(verify_signed_message(&signed_message).unwrap());
+    assert!(get_cid(MessageTxAPI::SignedMessageAPI(signed_message)).is_ok());
+
+    // Multisig proposal and payment channel to a f410 address
+    let propose_message = proposal_multisig_message(
+        "t01004".to_string(),
+        f410_address.clone(),
+        key.address.clone(),
+        "1000".to_string(),
+        2,
+        25000,
+        "2500".to_string(),
+        "2500".to_string(),
+        0,
+        "".to_string(),
+    )
+    .unwrap();
+
+    match deserialize_params(
+        propose_message.params,
+        "fil/5/multisig".to_string(),
+        multisig::MethodMultisig::Propose as u64,
+    )
+    .unwrap()
+    {
+        MessageParams::ProposeParamsMultisig(params) => {
+            assert_eq!(params.to, f410_address)
+        }
+        _ => panic!("Should be propose params"),
+    }
+
+    assert!(create_pymtchan(
+        key.address.clone(),
+        f410_address,
+        "1000".to_string(),
+        3,
+        25000,
+        "2500".to_string(),
+        "2500".to_string(),
+    )
+    .is_ok());
+}
+
+#[test]
+fn eth_transaction_delegated_signature() {
+    let test_value = common::load_test_vectors("../test_vectors/wallet.json").unwrap();
+    let mnemonic = test_value["mnemonic"].as_str().unwrap();
+    let key = key_derive(mnemonic, "m/44'/60'/0'/0/0", "", "en").unwrap();
+    let f410_address =
+        address::f410_address_from_public_key(&key.public_key.to_vec(), true).unwrap();
+
+    let transaction = eth::EthTransactionAPI {
+        chain_id: eth::CALIBNET_CHAIN_ID,
+        nonce: 1,
+        to: Some("0xd388ab098ed3e84c0d808776440b48f685198498".to_string()),
+        value: "1000000000000000000".to_string(),
+        max_fee_per_gas: "1500000000".to_string(),
+        max_priority_fee_per_gas: "100000".to_string(),
+        gas_limit: 1000000,
+        input: "0xa9059cbb".to_string(),
+    };
+
+    assert_eq!(
+        hex::encode(eth::eth_transaction_serialize(&transaction).unwrap()),
+        "02f68304cb2f01830186a08459682f00830f424094d388ab098ed3e84c0d808776440b48f685198498880de0b6b3a764000084a9059cbbc0"
+    );
+    assert_eq!(
+        hex::encode(eth::eth_transaction_digest(&transaction).unwrap()),
+        "3c207f3658862bc658291907762b07ee14f3088bc310ee56f3d952de3e7276dc"
+    );
+
+    let signature = match eth::eth_transaction_sign(&transaction, &key.private_key).unwrap() {
+        Signature::SignatureDelegated(signature) => signature,
+        _ => panic!("Expected a delegated signature"),
+    };
+    let signed_transaction =
+        eth::eth_transaction_serialize_signed(&transaction, &signature).unwrap();
+    assert_eq!(signed_transaction[0], eth::EIP1559_TX_TYPE);
+
+    // Filecoin signed message with a delegated signature
+    let signed_message =
+        eth::eth_transaction_to_signed_message(&transaction, &signature, true).unwrap();
+    assert_eq!(signed_message.message.from, f410_address);
+    assert_eq!(
+        signed_message.message.to,
+        address::eth_address_to_f410(transaction.to.as_ref().unwrap(), true).unwrap()
+    );
+    assert_eq!(signed_message.message.method, eth::METHOD_INVOKE_CONTRACT);
+    assert_eq!(signed_message.signature.sig_type, 3);
+
+    assert!(eth::verify_eth_signed_message(&signed_message, eth::CALIBNET_CHAIN_ID).unwrap());
+    assert!(!eth::verify_eth_signed_message(&signed_message, eth::MAINNET_CHAIN_ID).unwrap());
+    assert!(verify_signed_message(&signed_message).is_err());
+
+    let (transaction_back, signature_back) =
+        eth::eth_transaction_from_signed_message(&signed_message, eth::CALIBNET_CHAIN_ID).unwrap();
+    assert_eq!(transaction_back, transaction);
+    assert_eq!(signature_back.0.to_vec(), signature.0.to_vec());
+
+    // CBOR round trip keeps the delegated signature
+    let signed_message_cbor = CborBuffer(
+        to_vec(&api::AnySignedMessage {
+            message: api::AnyUnsignedMessage::try_from(&signed_message.message).unwrap(),
+            signature: api::AnySignature::try_from(&signed_message.signature).unwrap(),
+        })
+        .unwrap(),
+    );
+    match transaction_parse(&signed_message_cbor, true).unwrap() {
+        MessageTxAPI::SignedMessageAPI(parsed) => assert_eq!(parsed, signed_message),
+        MessageTxAPI::UnsignedMessageAPI(_) => panic!("Should be a signed message"),
+    }
+
+    // Contract creation goes through the Ethereum Address Manager
+    let deploy = eth::EthTransactionAPI {
+        to: None,
+        input: "0x6080".to_string(),
+        ..transaction
+    };
+    let signature = match eth::eth_transaction_sign(&deploy, &key.private_key).unwrap() {
+        Signature::SignatureDelegated(signature) => signature,
+        _ => panic!("Expected a delegated signature"),
+    };
+    let signed_message = eth::eth_transaction_to_signed_message(&deploy, &signature, true).unwrap();
+    assert_eq!(signed_message.message.to, "t010");
+    assert_eq!(signed_message.message.method, eth::METHOD_CREATE_EXTERNAL);
+    assert!(eth::verify_eth_signed_message(&signed_message, eth::CALIBNET_CHAIN_ID).unwrap());
+}
+
 #[test]
 fn verify_invalid_signature() {
     let test_value = common::load_test_vectors("../test_vectors/verify_signature.json").unwrap();
@@ -397,6 +1410,70 @@ fn test_verify_aggregated_signature() {
     assert!(verify_aggregated_signature(&sig, &cbor_messages[..]).unwrap());
 }
 
+#[test]
+fn aggregate_bls_signatures() {
+    let keys: Vec<_> = (0..4).map(|_| key_generate_bls(true).unwrap()).collect();
+
+    let messages: Vec<UnsignedMessageAPI> = keys
+        .iter()
+        .map(|key| UnsignedMessageAPI {
+            to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+            from: key.address.clone(),
+            nonce: 1,
+            value: "100000".to_string(),
+            gas_limit: 25000,
+            gas_fee_cap: "2500".to_string(),
+            gas_premium: "2500".to_string(),
+            method: 0,
+            params: "".to_string(),
+        })
+        .collect();
+
+    let sigs: Vec<SignatureBLS> = messages
+        .iter()
+        .zip(keys.iter())
+        .map(
+            |(message, key)| match transaction_sign_raw_with_signer(message, key).unwrap() {
+                Signature::SignatureBLS(sig) => sig,
+                _ => panic!("Expected a BLS signature"),
+            },
+        )
+        .collect();
+
+    let cbor_messages: Vec<CborBuffer> = messages
+        .iter()
+        .map(|message| transaction_serialize(message).unwrap())
+        .collect();
+
+    let aggregated_signature = aggregate_signatures(&sigs).unwrap();
+    assert!(verify_aggregated_signature(&aggregated_signature, &cbor_messages).unwrap());
+
+    // Incremental aggregation gives the same signature
+    let partial = aggregate_signatures(&sigs[..2]).unwrap();
+    let incremental =
+        aggregate_signatures(&[partial, SignatureBLS(sigs[2].0), SignatureBLS(sigs[3].0)]).unwrap();
+    assert_eq!(incremental.as_bytes(), aggregated_signature.as_bytes());
+
+    // Missing a message
+    assert!(
+        !verify_aggregated_signature(&aggregated_signature, &cbor_messages[..3]).unwrap_or(false)
+    );
+
+    // A non-BLS sender can't be part of an aggregated signature
+    let mut secp_message = messages[3].clone();
+    secp_message.from = "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba".to_string();
+    let mixed_messages: Vec<CborBuffer> = messages[..3]
+        .iter()
+        .chain(std::iter::once(&secp_message))
+        .map(|message| transaction_serialize(message).unwrap())
+        .collect();
+    assert!(!verify_aggregated_signature(&aggregated_signature, &mixed_messages).unwrap());
+
+    assert!(aggregate_signatures(&[]).is_err());
+    // Not a valid point
+    assert!(aggregate_signatures(&[SignatureBLS([0xffu8; 96])]).is_err());
+}
+
 #[test]
 fn payment_channel_creation_bls_signing() {
     let test_value = common::load_test_vectors("../test_vectors/payment_channel.json").unwrap();
@@ -972,3 +2049,412 @@ fn test_multisig_v1_deserialize() {
         MessageParams::ConstructorParamsMultisig(expected_params.into())
     );
 }
+
+#[test]
+fn test_deserialize_return() {
+    // Init `Exec` return of the payment channel created on a Lotus devnet
+    // (`test_vectors/payment_channel.json`), assigned to t01234
+    let exec_return = deserialize_return(
+        "gkMA0glVAnASWJkpWtoqhvfkj5DfG2tIaUWt".to_string(),
+        "fil/5/init".to_string(),
+        MethodInit::Exec as u64,
+    )
+    .unwrap();
+
+    match exec_return {
+        MessageReturn::ExecReturn(exec_return) => {
+            assert_eq!(exec_return.id_address, "t01234");
+            assert_eq!(
+                exec_return.robust_address,
+                "t2oajfrgjjllncvbxx4shzbxy3nnegsrnnk3tq2tq"
+            );
+        }
+        _ => panic!("Should be an exec return"),
+    }
+
+    // Multisig `Propose` return: transaction 5 applied with exit code 0
+    let propose_return = deserialize_return(
+        "hAX1AEA=".to_string(),
+        "fil/5/multisig".to_string(),
+        multisig::MethodMultisig::Propose as u64,
+    )
+    .unwrap();
+
+    assert_eq!(
+        propose_return,
+        MessageReturn::ProposeReturnMultisig(ProposeReturnMultisig {
+            txn_id: 5,
+            applied: true,
+            code: 0,
+            ret: "".to_string(),
+        })
+    );
+
+    assert!(deserialize_return(
+        "hAX1AEA=".to_string(),
+        "fil/5/init".to_string(),
+        MethodInit::Exec as u64,
+    )
+    .is_err());
+    assert!(deserialize_return(
+        "hAX1AEA=".to_string(),
+        "fil/5/multisig".to_string(),
+        multisig::MethodMultisig::Approve as u64,
+    )
+    .is_err());
+}
+
+#[test]
+fn transaction_sign_strict_mode() {
+    let private_key = PrivateKey([1u8; 32]);
+    let key = key_recover(&private_key, true).unwrap();
+
+    let message = UnsignedMessageAPI {
+        to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+        from: key.address,
+        nonce: 1,
+        value: "100000".to_string(),
+        gas_limit: 25000,
+        gas_fee_cap: "2500".to_string(),
+        gas_premium: "2500".to_string(),
+        method: 0,
+        params: "".to_string(),
+    };
+
+    assert!(validation::validate_message(&message).is_valid());
+    assert_eq!(
+        transaction_sign_strict(&message, &private_key).unwrap(),
+        transaction_sign(&message, &private_key).unwrap()
+    );
+
+    // The default mode signs what the node would reject, the strict mode refuses it
+    let invalid_message = UnsignedMessageAPI {
+        gas_fee_cap: "100".to_string(),
+        ..message
+    };
+
+    assert!(transaction_sign(&invalid_message, &private_key).is_ok());
+    match transaction_sign_strict(&invalid_message, &private_key) {
+        Err(SignerError::InvalidMessage(report)) => {
+            assert_eq!(report.errors.len(), 1);
+            assert_eq!(report.errors[0].field, "gas_fee_cap");
+        }
+        _ => panic!("Should refuse to sign an invalid message"),
+    }
+}
+
+#[test]
+fn builders_accept_token_amounts() {
+    use std::str::FromStr;
+    use token::TokenAmount;
+
+    let amount = TokenAmount::from_str("1.5 FIL").unwrap();
+
+    // Strings in attoFIL are still accepted, as well as amounts with a unit
+    assert_eq!(
+        create_voucher("t01003".to_string(), 0, 0, &amount, 0, 1, 0).unwrap(),
+        create_voucher(
+            "t01003".to_string(),
+            0,
+            0,
+            "1500000000000000000".to_string(),
+            0,
+            1,
+            0
+        )
+        .unwrap()
+    );
+    assert!(create_voucher("t01003".to_string(), 0, 0, "1.5", 0, 1, 0).is_err());
+
+    let multisig = create_multisig(
+        "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba".to_string(),
+        vec!["t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba".to_string()],
+        amount,
+        1,
+        1,
+        -1,
+        0,
+        25000,
+        "2500".to_string(),
+        "2500".to_string(),
+    )
+    .unwrap();
+    assert_eq!(multisig.value, "1500000000000000000");
+
+    let pymtchan = create_pymtchan(
+        "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba".to_string(),
+        "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+        "250 milliFIL",
+        1,
+        25000,
+        "2500".to_string(),
+        "2500".to_string(),
+    )
+    .unwrap();
+    assert_eq!(pymtchan.value, "250000000000000000");
+}
+
+#[test]
+fn message_builder_matches_helpers() {
+    use std::str::FromStr;
+
+    let sender = "t1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba";
+    let pch_address = "t2oajfrgjjllncvbxx4shzbxy3nnegsrnnk3tq2tq";
+
+    let message = builder::MessageBuilder::new(Address::from_str(sender).unwrap())
+        .nonce(1)
+        .value(token::TokenAmount::from_str("1 FIL").unwrap())
+        .gas_limit(25000)
+        .gas_fee_cap(token::TokenAmount::from(2500))
+        .gas_premium(token::TokenAmount::from(2500))
+        .call(&builder::CreateMultisig {
+            signers: vec![Address::from_str(sender).unwrap()],
+            num_approvals_threshold: 1,
+            unlock_duration: 0,
+            start_epoch: 0,
+        })
+        .build()
+        .unwrap();
+
+    let expected = create_multisig(
+        sender.to_string(),
+        vec![sender.to_string()],
+        "1 FIL",
+        1,
+        1,
+        0,
+        0,
+        25000,
+        "2500".to_string(),
+        "2500".to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(message, expected);
+    assert_eq!(message.to, "t01");
+    assert_eq!(message.method, MethodInit::Exec as u64);
+
+    let settle = builder::MessageBuilder::new(Address::from_str(sender).unwrap())
+        .to(Address::from_str(pch_address).unwrap())
+        .nonce(2)
+        .gas_limit(25000)
+        .gas_fee_cap(token::TokenAmount::from(2500))
+        .gas_premium(token::TokenAmount::from(2500))
+        .call(&builder::SettlePaymentChannel)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        settle,
+        settle_pymtchan(
+            pch_address.to_string(),
+            sender.to_string(),
+            2,
+            25000,
+            "2500".to_string(),
+            "2500".to_string(),
+        )
+        .unwrap()
+    );
+
+    // Only the builder validates the message, the helpers keep their arguments as they are
+    let settle = settle_pymtchan(
+        pch_address.to_string(),
+        "t01234".to_string(),
+        2,
+        25000,
+        "100".to_string(),
+        "2500".to_string(),
+    )
+    .unwrap();
+    assert_eq!(settle.from, "t01234");
+    assert_eq!(settle.gas_fee_cap, "100");
+
+    match builder::MessageBuilder::new(Address::from_str(sender).unwrap())
+        .to(Address::from_str(pch_address).unwrap())
+        .gas_limit(25000)
+        .gas_fee_cap(token::TokenAmount::from(100))
+        .gas_premium(token::TokenAmount::from(2500))
+        .call(&builder::SettlePaymentChannel)
+        .build()
+    {
+        Err(SignerError::InvalidMessage(report)) => {
+            assert_eq!(report.errors[0].field, "gas_fee_cap")
+        }
+        _ => panic!("Should be an invalid message"),
+    }
+
+    // `create_multisig` always sends to `t01`, the builder uses the network of the sender
+    let mainnet_sender = "f1d2xrzcslx7xlbbylc5c3d5lvandqw4iwl6epxba";
+    let multisig = create_multisig(
+        mainnet_sender.to_string(),
+        vec![mainnet_sender.to_string()],
+        "0",
+        1,
+        1,
+        0,
+        0,
+        25000,
+        "2500".to_string(),
+        "2500".to_string(),
+    )
+    .unwrap();
+    assert_eq!(multisig.to, "t01");
+    assert_eq!(multisig.from, mainnet_sender);
+}
+
+#[test]
+fn signing_bundle() {
+    use bundle::{BundleMessage, SigningBundle};
+    use std::str::FromStr;
+
+    let secp256k1_key = key_recover(&PrivateKey([1u8; 32]), true).unwrap();
+    let bls_key = key_generate_bls(true).unwrap();
+
+    let transfer = UnsignedMessageAPI {
+        to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+        from: bls_key.address.clone(),
+        nonce: 1,
+        value: "1500000000000000000".to_string(),
+        gas_limit: 25000,
+        gas_fee_cap: "2500".to_string(),
+        gas_premium: "2500".to_string(),
+        method: 0,
+        params: "".to_string(),
+    };
+    let proposal = proposal_multisig_message(
+        "t01004".to_string(),
+        "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+        secp256k1_key.address.clone(),
+        "1 FIL",
+        2,
+        25000,
+        "2500".to_string(),
+        "2500".to_string(),
+        0,
+        "".to_string(),
+    )
+    .unwrap();
+
+    let messages = vec![
+        BundleMessage {
+            message: transfer.clone(),
+            actor_type: None,
+        },
+        BundleMessage {
+            message: proposal.clone(),
+            actor_type: Some("fil/5/multisig".to_string()),
+        },
+    ];
+
+    let mut signing_bundle = bundle::bundle_create(
+        messages.clone(),
+        true,
+        Some(token::TokenAmount::from_str("0.001 FIL").unwrap()),
+    )
+    .unwrap();
+
+    // The bundle goes through JSON to the offline signer
+    let json = serde_json::to_string(&signing_bundle).unwrap();
+    let mut offline_bundle: SigningBundle = serde_json::from_str(&json).unwrap();
+    assert_eq!(offline_bundle, signing_bundle);
+
+    let summary = bundle::bundle_inspect(&offline_bundle).unwrap();
+    assert_eq!(summary[0].value, "1.5 FIL");
+    assert_eq!(summary[0].max_fee, "0.0000000000625 FIL");
+    assert_eq!(
+        summary[1].cid,
+        get_cid(MessageTxAPI::UnsignedMessageAPI(proposal)).unwrap()
+    );
+    match &summary[1].params {
+        Some(MessageParams::ProposeParamsMultisig(params)) => {
+            assert_eq!(params.value, "1000000000000000000")
+        }
+        _ => panic!("Should be propose params"),
+    }
+
+    // Partial signature
+    assert_eq!(
+        bundle::bundle_sign(&mut offline_bundle, &secp256k1_key.private_key).unwrap(),
+        1
+    );
+    assert!(bundle::bundle_finalize(&offline_bundle).is_err());
+    assert!(bundle::bundle_sign(&mut offline_bundle, &secp256k1_key.private_key).is_err());
+
+    assert_eq!(
+        bundle::bundle_sign_with_signer(&mut offline_bundle, &bls_key).unwrap(),
+        1
+    );
+    let signed_messages = bundle::bundle_finalize(&offline_bundle).unwrap();
+    assert_eq!(signed_messages.len(), 2);
+    assert!(signed_messages
+        .iter()
+        .all(|signed_message| verify_signed_message(signed_message).unwrap()));
+
+    // Tampered bundles are rejected
+    signing_bundle.entries[0].message.value = "2000000000000000000".to_string();
+    assert!(bundle::bundle_inspect(&signing_bundle).is_err());
+
+    let mut tampered = offline_bundle.clone();
+    tampered.entries[1].decoded_params = None;
+    assert!(bundle::bundle_inspect(&tampered).is_err());
+
+    let mut tampered = offline_bundle;
+    tampered.version = 2;
+    assert!(bundle::bundle_finalize(&tampered).is_err());
+
+    // Fee ceiling and network
+    assert!(
+        bundle::bundle_create(messages.clone(), true, Some(token::TokenAmount::from(1000)))
+            .is_err()
+    );
+    assert!(bundle::bundle_create(messages, false, None).is_err());
+}
+
+#[test]
+fn qr_transport() {
+    let key = key_recover(&PrivateKey([1u8; 32]), true).unwrap();
+
+    let message = UnsignedMessageAPI {
+        to: "t17uoq6tp427uzv7fztkbsnn64iwotfrristwpryy".to_string(),
+        from: key.address.clone(),
+        nonce: 1,
+        value: "100000".to_string(),
+        gas_limit: 25000,
+        gas_fee_cap: "2500".to_string(),
+        gas_premium: "2500".to_string(),
+        method: 0,
+        params: "".to_string(),
+    };
+    let signed_message = transaction_sign(&message, &key.private_key).unwrap();
+
+    // Drop the first parts, the fountain coded ones make up for them
+    let parts = transport::qr_encode(
+        &MessageTxAPI::SignedMessageAPI(signed_message.clone()),
+        20,
+        30,
+    )
+    .unwrap();
+    assert!(parts.iter().all(|part| part.starts_with("FILTX/")));
+
+    match transport::qr_decode(&parts[2..], true).unwrap() {
+        MessageTxAPI::SignedMessageAPI(decoded) => assert_eq!(decoded, signed_message),
+        _ => panic!("Expected a signed message"),
+    }
+
+    let parts =
+        transport::qr_encode(&MessageTxAPI::UnsignedMessageAPI(message.clone()), 20, 0).unwrap();
+    match transport::qr_decode(&parts, true).unwrap() {
+        MessageTxAPI::UnsignedMessageAPI(decoded) => assert_eq!(decoded, message),
+        _ => panic!("Expected an unsigned message"),
+    }
+
+    // Parts of two different messages can't be mixed
+    let other_parts =
+        transport::qr_encode(&MessageTxAPI::SignedMessageAPI(signed_message), 20, 0).unwrap();
+    let mixed = vec![parts[0].clone(), other_parts[1].clone()];
+    assert!(transport::qr_decode(&mixed, true).is_err());
+
+    // Incomplete
+    assert!(transport::qr_decode(&parts[1..], true).is_err());
+}